@@ -0,0 +1,166 @@
+use std::time::SystemTime;
+
+use tokio::{
+    io::{self, AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader, Lines, ReadHalf},
+    net::TcpStream,
+    sync::mpsc::{self, UnboundedSender},
+};
+use tokio_native_tls::{native_tls, TlsConnector};
+use twitch_irc::message::{AsRawIRC, IRCMessage, IRCPrefix};
+
+use crate::core::config::IrcConfig;
+use crate::error::{Error, Result};
+
+use super::{ChatEvent, ChatSource};
+
+trait Stream: AsyncRead + AsyncWrite + Unpin + Send {}
+
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> Stream for T {}
+
+type Connection = Box<dyn Stream>;
+
+/// Plain IRC client for self-hosted servers
+pub struct IrcSource {
+    config: IrcConfig,
+    nick: String,
+    lines: Option<Lines<BufReader<ReadHalf<Connection>>>>,
+    // Writes go through a separate task, so they are never interrupted by a pending read
+    writer: Option<UnboundedSender<IRCMessage>>,
+}
+
+impl IrcSource {
+    pub fn new(config: IrcConfig) -> Self {
+        Self {
+            nick: config.nick.clone(),
+            config,
+            lines: None,
+            writer: None,
+        }
+    }
+
+    fn send(&self, command: &str, params: Vec<String>) {
+        if let Some(writer) = &self.writer {
+            let _ = writer.send(IRCMessage::new_simple(command.to_owned(), params));
+        }
+    }
+
+    fn handle_message(&mut self, message: IRCMessage) -> Option<ChatEvent> {
+        match message.command.as_str() {
+            "PING" => {
+                self.send("PONG", message.params);
+            }
+            // RPL_WELCOME, registration is complete and channels can be joined
+            "001" => {
+                for channel in &self.config.channels {
+                    self.send("JOIN", vec![channel.clone()]);
+                }
+            }
+            // ERR_NICKNAMEINUSE
+            "433" => {
+                self.nick.push('_');
+                self.send("NICK", vec![self.nick.clone()]);
+            }
+            "PRIVMSG" => {
+                let author = match message.prefix {
+                    Some(IRCPrefix::Full { nick, .. }) => nick,
+                    Some(IRCPrefix::HostOnly { host }) => host,
+                    None => String::new(),
+                };
+
+                let mut params = message.params.into_iter();
+
+                if let Some((channel, text)) = params.next().zip(params.next()) {
+                    return Some(ChatEvent {
+                        author,
+                        channel,
+                        badges: Vec::new(),
+                        text,
+                        timestamp: SystemTime::now(),
+                    });
+                }
+            }
+            _ => {}
+        }
+
+        None
+    }
+}
+
+impl ChatSource for IrcSource {
+    async fn connect(&mut self) -> Result<()> {
+        let host = self.config.host.as_str();
+        let port = self.config.port();
+
+        let tcp = TcpStream::connect((host, port)).await?;
+
+        let connection: Connection = if self.config.tls {
+            let connector = TlsConnector::from(native_tls::TlsConnector::new()?);
+            Box::new(connector.connect(host, tcp).await?)
+        } else {
+            Box::new(tcp)
+        };
+
+        let (reader, mut writer) = io::split(connection);
+        let (sender, mut receiver) = mpsc::unbounded_channel::<IRCMessage>();
+
+        tokio::spawn(async move {
+            while let Some(message) = receiver.recv().await {
+                let raw = format!("{}\r\n", message.as_raw_irc());
+
+                if writer.write_all(raw.as_bytes()).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        self.lines = Some(BufReader::new(reader).lines());
+        self.writer = Some(sender);
+
+        self.send("NICK", vec![self.nick.clone()]);
+        self.send(
+            "USER",
+            vec![
+                self.nick.clone(),
+                String::from("0"),
+                String::from("*"),
+                String::from("nvim-chaos"),
+            ],
+        );
+
+        Ok(())
+    }
+
+    async fn next_event(&mut self) -> Result<Option<ChatEvent>> {
+        loop {
+            let Some(lines) = self.lines.as_mut() else {
+                return Ok(None);
+            };
+
+            // The server never closes the connection on its own, so the end of the stream is an error
+            let Some(line) = lines.next_line().await? else {
+                return Err(Error::Chat(format!(
+                    "connection to {} closed",
+                    self.config.host
+                )));
+            };
+
+            let Ok(message) = IRCMessage::parse(&line) else {
+                continue;
+            };
+
+            if let Some(event) = self.handle_message(message) {
+                return Ok(Some(event));
+            }
+        }
+    }
+
+    fn can_reply(&self) -> bool {
+        true
+    }
+
+    async fn reply(&mut self, channel: &str, text: &str) -> Result<()> {
+        self.send("PRIVMSG", vec![channel.to_owned(), text.to_owned()]);
+
+        Ok(())
+    }
+}
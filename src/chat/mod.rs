@@ -0,0 +1,143 @@
+use std::time::{Duration, SystemTime};
+
+use nvim_oxi::{libuv::AsyncHandle, schedule};
+use tokio::sync::mpsc::{self, UnboundedSender};
+
+use crate::commands::registry::{Invocation, Registry};
+use crate::core::config::{Config, Role};
+use crate::core::plugin::Plugin;
+use crate::error::Result;
+
+pub mod irc;
+#[cfg(unix)]
+pub mod socket;
+pub mod twitch;
+
+/// Events older than this are dropped, e.g. a backlog delivered after a reconnect
+const STALE_AFTER: Duration = Duration::from_secs(60);
+
+/// Chat message normalized across all chat backends
+#[derive(Debug, Clone)]
+pub struct ChatEvent {
+    pub author: String,
+    /// Channel the message was sent to, empty when the source has no channels
+    pub channel: String,
+    pub badges: Vec<String>,
+    pub text: String,
+    pub timestamp: SystemTime,
+}
+
+impl ChatEvent {
+    pub fn is_stale(&self) -> bool {
+        self.timestamp
+            .elapsed()
+            .is_ok_and(|elapsed| elapsed > STALE_AFTER)
+    }
+}
+
+/// Chat message sent back by the plugin
+#[derive(Debug)]
+pub struct Reply {
+    pub channel: String,
+    pub text: String,
+}
+
+#[derive(Debug)]
+pub struct CommandPayload {
+    pub command: Invocation,
+    pub author: String,
+    pub role: Role,
+    pub channel: String,
+    /// Reply path to the source, `None` when the source can't write to chat
+    pub replies: Option<UnboundedSender<Reply>>,
+}
+
+impl CommandPayload {
+    pub fn can_reply(&self) -> bool {
+        self.replies.is_some()
+    }
+
+    /// Answers the author of the command in chat
+    pub fn reply(&self, text: &str) {
+        if let Some(replies) = &self.replies {
+            let _ = replies.send(Reply {
+                channel: self.channel.clone(),
+                text: format!("@{} {text}", self.author),
+            });
+        }
+    }
+}
+
+/// Backend that produces chat events, e.g. Twitch IRC
+pub trait ChatSource {
+    /// Connects to the backend. Called once before any event is requested.
+    async fn connect(&mut self) -> Result<()>;
+
+    /// Waits for the next chat event. Returns `None` when the source is closed.
+    async fn next_event(&mut self) -> Result<Option<ChatEvent>>;
+
+    /// Whether the source is able to send messages, e.g. it's logged in
+    fn can_reply(&self) -> bool {
+        false
+    }
+
+    async fn reply(&mut self, _channel: &str, _text: &str) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Connects the source and feeds every matched command into the plugin
+#[tokio::main(flavor = "current_thread")]
+pub async fn run<S: ChatSource>(
+    mut source: S,
+    handle: AsyncHandle,
+    sender: UnboundedSender<CommandPayload>,
+    config: Config,
+    registry: &Registry,
+) -> Result<()> {
+    source.connect().await?;
+
+    let (reply_sender, mut replies) = mpsc::unbounded_channel::<Reply>();
+    let reply_sender = source.can_reply().then_some(reply_sender);
+
+    loop {
+        tokio::select! {
+            event = source.next_event() => {
+                let Some(event) = event? else {
+                    break;
+                };
+
+                if event.is_stale() {
+                    continue;
+                }
+
+                let command = registry
+                    .parse(&event.text)
+                    .filter(|command| config.is_command_enabled(&event.channel, command.key));
+
+                if let Some(command) = command {
+                    sender
+                        .send(CommandPayload {
+                            command,
+                            role: Role::from_badges(&event.badges),
+                            author: event.author,
+                            channel: event.channel,
+                            replies: reply_sender.clone(),
+                        })
+                        .unwrap();
+                    handle.send()?;
+                }
+            }
+            Some(reply) = replies.recv() => {
+                // A failed reply, e.g. a rate limit, must not stop the source
+                if let Err(error) = source.reply(&reply.channel, &reply.text).await {
+                    schedule(move |()| {
+                        Plugin::err(error.to_string().as_str());
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
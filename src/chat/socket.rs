@@ -0,0 +1,124 @@
+use std::{os::unix::fs::FileTypeExt, path::PathBuf, time::SystemTime};
+
+use serde::Deserialize;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::{UnixListener, UnixStream},
+    sync::mpsc::{self, UnboundedReceiver, UnboundedSender},
+};
+
+use crate::error::{Error, Result};
+
+use super::{ChatEvent, ChatSource};
+
+/// Line of the socket protocol, e.g. `{"author": "bot", "text": "!vimhell"}`
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SocketEvent {
+    #[serde(default = "default_author")]
+    author: String,
+    #[serde(default)]
+    channel: String,
+    text: String,
+    #[serde(default)]
+    badges: Vec<String>,
+}
+
+fn default_author() -> String {
+    String::from("local")
+}
+
+impl From<SocketEvent> for ChatEvent {
+    fn from(event: SocketEvent) -> Self {
+        ChatEvent {
+            author: event.author,
+            channel: event.channel,
+            badges: event.badges,
+            text: event.text,
+            timestamp: SystemTime::now(),
+        }
+    }
+}
+
+pub struct SocketSource {
+    path: PathBuf,
+    events: Option<UnboundedReceiver<ChatEvent>>,
+}
+
+impl SocketSource {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            events: None,
+        }
+    }
+}
+
+impl ChatSource for SocketSource {
+    async fn connect(&mut self) -> Result<()> {
+        // The socket file outlives the previous Neovim session, anything else is left alone
+        if let Ok(metadata) = std::fs::symlink_metadata(&self.path) {
+            if !metadata.file_type().is_socket() {
+                return Err(Error::Chat(format!(
+                    "{} exists and is not a socket",
+                    self.path.display()
+                )));
+            }
+
+            // Another Neovim still listening keeps its socket
+            if std::os::unix::net::UnixStream::connect(&self.path).is_ok() {
+                return Err(Error::Chat(format!(
+                    "{} is already in use",
+                    self.path.display()
+                )));
+            }
+
+            std::fs::remove_file(&self.path)?;
+        }
+
+        let listener = UnixListener::bind(&self.path)?;
+        let (sender, receiver) = mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(read_events(stream, sender.clone()));
+            }
+        });
+
+        self.events = Some(receiver);
+
+        Ok(())
+    }
+
+    async fn next_event(&mut self) -> Result<Option<ChatEvent>> {
+        let Some(events) = self.events.as_mut() else {
+            return Ok(None);
+        };
+
+        Ok(events.recv().await)
+    }
+}
+
+async fn read_events(stream: UnixStream, sender: UnboundedSender<ChatEvent>) {
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+
+    while let Ok(Some(line)) = lines.next_line().await {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        match serde_json::from_str::<SocketEvent>(&line) {
+            Ok(event) => {
+                if sender.send(event.into()).is_err() {
+                    break;
+                }
+            }
+            Err(error) => {
+                let _ = writer
+                    .write_all(format!("error: {error}\n").as_bytes())
+                    .await;
+            }
+        }
+    }
+}
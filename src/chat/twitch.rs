@@ -0,0 +1,90 @@
+use tokio::sync::mpsc::UnboundedReceiver;
+use twitch_irc::{
+    login::StaticLoginCredentials, message::ServerMessage, ClientConfig, SecureTCPTransport,
+    TwitchIRCClient,
+};
+
+use crate::error::{Error, Result};
+
+use super::{ChatEvent, ChatSource};
+
+type Client = TwitchIRCClient<SecureTCPTransport, StaticLoginCredentials>;
+
+pub struct TwitchSource {
+    channels: Vec<String>,
+    credentials: Option<StaticLoginCredentials>,
+    client: Option<Client>,
+    incoming_messages: Option<UnboundedReceiver<ServerMessage>>,
+}
+
+impl TwitchSource {
+    pub fn new(channels: Vec<String>) -> Self {
+        Self {
+            channels,
+            credentials: None,
+            client: None,
+            incoming_messages: None,
+        }
+    }
+
+    /// Logs in as the bot account instead of anonymously, which allows replies
+    pub fn with_login(mut self, login: String, token: String) -> Self {
+        self.credentials = Some(StaticLoginCredentials::new(login, Some(token)));
+        self
+    }
+}
+
+impl ChatSource for TwitchSource {
+    async fn connect(&mut self) -> Result<()> {
+        let credentials = self
+            .credentials
+            .clone()
+            .unwrap_or_else(StaticLoginCredentials::anonymous);
+        let client_config = ClientConfig::new_simple(credentials);
+        let (incoming_messages, client) = Client::new(client_config);
+
+        for channel in &self.channels {
+            client.join(channel.clone())?;
+        }
+
+        self.client = Some(client);
+        self.incoming_messages = Some(incoming_messages);
+
+        Ok(())
+    }
+
+    async fn next_event(&mut self) -> Result<Option<ChatEvent>> {
+        let Some(incoming_messages) = self.incoming_messages.as_mut() else {
+            return Ok(None);
+        };
+
+        while let Some(message) = incoming_messages.recv().await {
+            if let ServerMessage::Privmsg(msg) = message {
+                return Ok(Some(ChatEvent {
+                    author: msg.sender.name,
+                    channel: msg.channel_login,
+                    badges: msg.badges.into_iter().map(|badge| badge.name).collect(),
+                    text: msg.message_text,
+                    timestamp: msg.server_timestamp.into(),
+                }));
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn can_reply(&self) -> bool {
+        self.credentials.is_some()
+    }
+
+    async fn reply(&mut self, channel: &str, text: &str) -> Result<()> {
+        if let Some(client) = &self.client {
+            client
+                .say(channel.to_owned(), text.to_owned())
+                .await
+                .map_err(|error| Error::Chat(error.to_string()))?;
+        }
+
+        Ok(())
+    }
+}
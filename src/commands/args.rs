@@ -0,0 +1,348 @@
+use thiserror::Error as ThisError;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArgKind {
+    /// Single word
+    Word,
+    /// Rest of the message
+    Text,
+    /// One of the listed words
+    Choice(&'static [&'static str]),
+    Integer {
+        min: i64,
+        max: i64,
+    },
+    /// Seconds written as `90`, `30s`, `2m` or `1m30s`
+    Duration,
+}
+
+#[derive(Debug, Clone)]
+pub struct ArgSpec {
+    pub name: &'static str,
+    pub kind: ArgKind,
+    pub required: bool,
+}
+
+impl ArgSpec {
+    pub fn new(name: &'static str, kind: ArgKind) -> Self {
+        Self {
+            name,
+            kind,
+            required: true,
+        }
+    }
+
+    pub fn optional(mut self) -> Self {
+        self.required = false;
+        self
+    }
+
+    pub fn usage(&self) -> String {
+        let name = match self.kind {
+            ArgKind::Choice(choices) => choices.join("|"),
+            _ => String::from(self.name),
+        };
+
+        if self.required {
+            format!("<{name}>")
+        } else {
+            format!("[{name}]")
+        }
+    }
+
+    fn parse(&self, value: &str) -> Result<ArgValue, ArgError> {
+        let name = self.name;
+
+        match self.kind {
+            ArgKind::Word | ArgKind::Text => Ok(ArgValue::Text(value.to_owned())),
+            ArgKind::Choice(choices) => choices
+                .iter()
+                .find(|x| x.eq_ignore_ascii_case(value))
+                .map(|x| ArgValue::Text(String::from(*x)))
+                .ok_or_else(|| ArgError::Choice {
+                    name,
+                    choices: choices.join(", "),
+                }),
+            ArgKind::Integer { min, max } => value
+                .parse()
+                .ok()
+                .filter(|x| (min..=max).contains(x))
+                .map(ArgValue::Integer)
+                .ok_or(ArgError::Range { name, min, max }),
+            // A mode of zero seconds would end at once and still use the cooldown
+            ArgKind::Duration => parse_duration(value)
+                .filter(|x| *x > 0)
+                .map(ArgValue::Duration)
+                .ok_or(ArgError::Duration { name }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArgValue {
+    Text(String),
+    Integer(i64),
+    Duration(u32),
+}
+
+/// Parsed arguments by name, optional arguments that weren't given are absent
+#[derive(Debug, Clone, Default)]
+pub struct Args {
+    values: Vec<(&'static str, ArgValue)>,
+}
+
+impl Args {
+    pub fn parse(specs: &[ArgSpec], text: &str) -> Result<Self, ArgError> {
+        let mut rest = text.trim();
+        let mut values = Vec::new();
+        // Error of a skipped optional argument, reported if no later one takes the value
+        let mut skipped = None;
+
+        for (index, spec) in specs.iter().enumerate() {
+            let (value, tail) = match spec.kind {
+                ArgKind::Text => (rest, ""),
+                _ => rest.split_once(' ').unwrap_or((rest, "")),
+            };
+
+            if value.is_empty() {
+                if spec.required {
+                    return Err(ArgError::Missing(spec.name));
+                }
+                continue;
+            }
+
+            match spec.parse(value) {
+                Ok(parsed) => {
+                    values.push((spec.name, parsed));
+                    rest = tail.trim_start();
+                    skipped = None;
+                }
+                // Optional arguments can be skipped, e.g. `!colorscheme tokyo 90s`
+                Err(error) if !spec.required && index + 1 < specs.len() => {
+                    skipped.get_or_insert(error);
+                }
+                Err(error) => return Err(skipped.unwrap_or(error)),
+            }
+        }
+
+        if let Some(error) = skipped {
+            return Err(error);
+        }
+
+        if !rest.is_empty() {
+            return Err(ArgError::Unexpected(String::from(rest)));
+        }
+
+        Ok(Self { values })
+    }
+
+    fn get(&self, name: &str) -> Option<&ArgValue> {
+        self.values
+            .iter()
+            .find(|(x, _)| *x == name)
+            .map(|(_, value)| value)
+    }
+
+    pub fn text(&self, name: &str) -> Option<&str> {
+        match self.get(name) {
+            Some(ArgValue::Text(text)) => Some(text),
+            _ => None,
+        }
+    }
+
+    pub fn integer(&self, name: &str) -> Option<i64> {
+        match self.get(name) {
+            Some(ArgValue::Integer(integer)) => Some(*integer),
+            _ => None,
+        }
+    }
+
+    pub fn duration(&self, name: &str) -> Option<u32> {
+        match self.get(name) {
+            Some(ArgValue::Duration(seconds)) => Some(*seconds),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, ThisError)]
+pub enum ArgError {
+    #[error("missing <{0}>")]
+    Missing(&'static str),
+
+    #[error("<{name}> must be one of {choices}")]
+    Choice { name: &'static str, choices: String },
+
+    #[error("<{name}> must be a number from {min} to {max}")]
+    Range {
+        name: &'static str,
+        min: i64,
+        max: i64,
+    },
+
+    #[error("<{name}> must be a duration like 30s or 2m")]
+    Duration { name: &'static str },
+
+    #[error("unexpected {0}")]
+    Unexpected(String),
+}
+
+/// Parses `90`, `30s`, `2m`, `1h` or combinations like `1m30s` into seconds
+pub fn parse_duration(value: &str) -> Option<u32> {
+    if value.is_empty() {
+        return None;
+    }
+
+    if let Ok(seconds) = value.parse() {
+        return Some(seconds);
+    }
+
+    let mut seconds: u32 = 0;
+    let mut number = String::new();
+
+    for char in value.chars() {
+        if char.is_ascii_digit() {
+            number.push(char);
+            continue;
+        }
+
+        let unit = match char.to_ascii_lowercase() {
+            's' => 1,
+            'm' => 60,
+            'h' => 60 * 60,
+            _ => return None,
+        };
+
+        let amount: u32 = number.parse().ok()?;
+        seconds = seconds.checked_add(amount.checked_mul(unit)?)?;
+        number.clear();
+    }
+
+    number.is_empty().then_some(seconds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHOICES: &[&str] = &["dark", "light"];
+
+    fn specs() -> Vec<ArgSpec> {
+        vec![
+            ArgSpec::new("name", ArgKind::Word),
+            ArgSpec::new("background", ArgKind::Choice(CHOICES)).optional(),
+            ArgSpec::new("duration", ArgKind::Duration).optional(),
+        ]
+    }
+
+    #[test]
+    fn parses_every_argument() {
+        let args = Args::parse(&specs(), "tokyo Light 2m").unwrap();
+
+        assert_eq!(args.text("name"), Some("tokyo"));
+        assert_eq!(args.text("background"), Some("light"));
+        assert_eq!(args.duration("duration"), Some(120));
+    }
+
+    #[test]
+    fn skips_optional_arguments() {
+        let args = Args::parse(&specs(), "tokyo 90s").unwrap();
+
+        assert_eq!(args.text("background"), None);
+        assert_eq!(args.duration("duration"), Some(90));
+
+        let args = Args::parse(&specs(), "tokyo").unwrap();
+
+        assert_eq!(args.text("background"), None);
+        assert_eq!(args.duration("duration"), None);
+    }
+
+    #[test]
+    fn reports_the_skipped_argument() {
+        let error = Args::parse(&specs(), "tokyo darkk").unwrap_err();
+
+        assert_eq!(
+            error,
+            ArgError::Choice {
+                name: "background",
+                choices: String::from("dark, light"),
+            }
+        );
+
+        let error = Args::parse(&specs(), "tokyo dark 2x").unwrap_err();
+
+        assert_eq!(error, ArgError::Duration { name: "duration" });
+    }
+
+    #[test]
+    fn reports_missing_arguments() {
+        assert_eq!(
+            Args::parse(&specs(), "  ").unwrap_err(),
+            ArgError::Missing("name")
+        );
+    }
+
+    #[test]
+    fn reports_extra_text() {
+        assert_eq!(
+            Args::parse(&specs(), "tokyo dark 30s junk").unwrap_err(),
+            ArgError::Unexpected(String::from("junk"))
+        );
+        assert_eq!(
+            Args::parse(&[], "foo bar").unwrap_err(),
+            ArgError::Unexpected(String::from("foo bar"))
+        );
+    }
+
+    #[test]
+    fn checks_integer_ranges() {
+        let specs = [ArgSpec::new("page", ArgKind::Integer { min: 1, max: 3 })];
+
+        assert_eq!(Args::parse(&specs, "3").unwrap().integer("page"), Some(3));
+        assert_eq!(
+            Args::parse(&specs, "4").unwrap_err(),
+            ArgError::Range {
+                name: "page",
+                min: 1,
+                max: 3,
+            }
+        );
+    }
+
+    #[test]
+    fn keeps_the_rest_of_the_text() {
+        let specs = [ArgSpec::new("text", ArgKind::Text)];
+        let args = Args::parse(&specs, " hello  chat ").unwrap();
+
+        assert_eq!(args.text("text"), Some("hello  chat"));
+    }
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(parse_duration("90"), Some(90));
+        assert_eq!(parse_duration("30s"), Some(30));
+        assert_eq!(parse_duration("2m"), Some(120));
+        assert_eq!(parse_duration("1m30s"), Some(90));
+        assert_eq!(parse_duration("1H"), Some(3600));
+    }
+
+    #[test]
+    fn rejects_zero_durations() {
+        let specs = [ArgSpec::new("duration", ArgKind::Duration).optional()];
+
+        assert_eq!(
+            Args::parse(&specs, "0s").unwrap_err(),
+            ArgError::Duration { name: "duration" }
+        );
+    }
+
+    #[test]
+    fn rejects_bad_durations() {
+        assert_eq!(parse_duration(""), None);
+        assert_eq!(parse_duration("m"), None);
+        assert_eq!(parse_duration("1m30"), None);
+        assert_eq!(parse_duration("5d"), None);
+        assert_eq!(parse_duration("99999999999"), None);
+        assert_eq!(parse_duration("100000000m"), None);
+    }
+}
@@ -0,0 +1,14 @@
+use nvim_oxi::api;
+
+use crate::error::Result;
+
+/// Deletes the group if it exists, a mode can be stopped twice, e.g. by a panic while paused
+pub fn delete(name: &str) -> Result<()> {
+    let exists: i64 = api::call_function("exists", (format!("#{name}"),))?;
+
+    if exists != 0 {
+        api::del_augroup_by_name(name)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use crate::error::Result;
+
+use super::{keymap::Keymaps, ModeCommand};
+
+const MODES: [&str; 3] = ["n", "x", "o"];
+
+/// Keys the streamer can't live without, whatever the config says
+pub const NEVER_BANNED: [&str; 4] = [":", "<Esc>", "<C-c>", "<CR>"];
+
+#[derive(Default, Clone, Debug)]
+pub struct Command {
+    pub key: String,
+    keymaps: Keymaps,
+}
+
+impl Command {
+    pub fn new(key: String) -> Self {
+        Self {
+            key,
+            keymaps: Keymaps::default(),
+        }
+    }
+}
+
+impl PartialEq for Command {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl ModeCommand for Command {
+    fn start(&self) -> Result<()> {
+        let message = format!("{} is banned by chat", self.key);
+
+        for mode in MODES {
+            self.keymaps.warn(mode, &self.key, &message)?;
+        }
+
+        Ok(())
+    }
+
+    fn is_valid(&self) -> Result<bool> {
+        Ok(!NEVER_BANNED
+            .iter()
+            .any(|x| x.eq_ignore_ascii_case(&self.key)))
+    }
+
+    fn stop(&self) -> Result<()> {
+        self.keymaps.clear()
+    }
+
+    fn name(&self) -> String {
+        format!("Banned Key - {}", self.key)
+    }
+}
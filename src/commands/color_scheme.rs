@@ -30,7 +30,7 @@ impl FromStr for Background {
         match value {
             "dark" => Ok(Background::Dark),
             "light" => Ok(Background::Light),
-            _ => Ok(Background::Default),
+            _ => Err(()),
         }
     }
 }
@@ -83,17 +83,7 @@ impl ModeCommand for Command {
     }
 
     fn is_valid(&self) -> Result<bool> {
-        let schemes: Array = api::call_function("getcompletion", Array::from_iter(["", "color"]))?;
-
-        for scheme in schemes {
-            unsafe {
-                if scheme.into_string_unchecked() == self.colorscheme {
-                    return Ok(true);
-                }
-            }
-        }
-
-        Ok(false)
+        Ok(installed()?.contains(&self.colorscheme))
     }
 
     fn stop(&self) -> Result<()> {
@@ -112,3 +102,47 @@ impl ModeCommand for Command {
         format!("Color Scheme - {}", self.colorscheme)
     }
 }
+
+/// Names of the installed color schemes
+pub(crate) fn installed() -> Result<Vec<String>> {
+    let schemes: Array = api::call_function("getcompletion", Array::from_iter(["", "color"]))?;
+
+    let schemes = schemes
+        .into_iter()
+        .map(|scheme| unsafe { scheme.into_string_unchecked() }.to_string())
+        .collect();
+
+    Ok(schemes)
+}
+
+/// Finds the installed color scheme closest to the name.
+/// Exact match wins, then prefix, substring and subsequence, preferring the shortest name.
+pub(crate) fn find(name: &str) -> Result<Option<String>> {
+    let name = name.to_lowercase();
+    let schemes = installed()?;
+
+    let matchers: [fn(&str, &str) -> bool; 4] = [
+        |scheme, name| scheme == name,
+        |scheme, name| scheme.starts_with(name),
+        |scheme, name| scheme.contains(name),
+        is_subsequence,
+    ];
+
+    for matcher in matchers {
+        let scheme = schemes
+            .iter()
+            .filter(|scheme| matcher(&scheme.to_lowercase(), &name))
+            .min_by_key(|scheme| scheme.len());
+
+        if let Some(scheme) = scheme {
+            return Ok(Some(scheme.clone()));
+        }
+    }
+
+    Ok(None)
+}
+
+fn is_subsequence(scheme: &str, name: &str) -> bool {
+    let mut chars = scheme.chars();
+    name.chars().all(|char| chars.any(|x| x == char))
+}
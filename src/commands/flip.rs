@@ -0,0 +1,334 @@
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use nvim_oxi::{
+    api::{
+        self,
+        opts::{BufDeleteOpts, CreateAugroupOpts, CreateAutocmdOpts, OptionOpts, OptionScope},
+        types::{AutocmdCallbackArgs, WindowConfig, WindowRelativeTo, WindowStyle},
+        Buffer, Window,
+    },
+    conversion::FromObject,
+    Array, Dictionary, Function,
+};
+
+use crate::core::plugin::Plugin;
+use crate::error::Result;
+
+use super::{augroup, ModeCommand};
+
+const GROUP: &str = "nvim-chaos-flip";
+
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Flip {
+    /// Every line is read from right to left
+    #[default]
+    Mirror,
+    /// The window is rotated by 180 degrees
+    UpsideDown,
+}
+
+/// Read-only float covering the window the mode started in
+#[derive(Debug, Clone)]
+struct Overlay {
+    buf: Buffer,
+    win: Window,
+    source: Window,
+    /// `wrap` and `foldenable` of the source, lines are only mirrored one to one without them
+    wrap: bool,
+    foldenable: bool,
+}
+
+/// Part of a line taking `width` screen columns, tabs are expanded to spaces
+struct Cell {
+    text: String,
+    width: usize,
+    /// Byte index of the character in the line
+    byte: usize,
+}
+
+#[derive(Default, Clone, Debug)]
+pub struct Command {
+    pub flip: Flip,
+    overlay: Rc<RefCell<Option<Overlay>>>,
+}
+
+impl Command {
+    pub fn new(flip: Flip) -> Self {
+        Self {
+            flip,
+            overlay: Rc::default(),
+        }
+    }
+}
+
+impl PartialEq for Command {
+    fn eq(&self, other: &Self) -> bool {
+        self.flip == other.flip
+    }
+}
+
+fn window_config(source: &Window) -> Result<WindowConfig> {
+    let config = WindowConfig::builder()
+        .relative(WindowRelativeTo::Window(source.clone()))
+        .style(WindowStyle::Minimal)
+        .width(source.get_width()?)
+        .height(source.get_height()?)
+        .row(0)
+        .col(0)
+        .focusable(false)
+        // Stays under the status window and other floats
+        .zindex(1)
+        .build();
+
+    Ok(config)
+}
+
+/// Options of the window alone, like `:setlocal`, new windows keep the global values
+fn local_options(win: &Window) -> OptionOpts {
+    OptionOpts::builder()
+        .scope(OptionScope::Local)
+        .win(win.clone())
+        .build()
+}
+
+fn win_info(source: &Window, key: &str) -> Result<usize> {
+    let info: Array = api::call_function("getwininfo", (source.handle(),))?;
+    let value = info
+        .into_iter()
+        .next()
+        .and_then(|x| Dictionary::from_object(x).ok())
+        .and_then(|x| x.get(key).cloned())
+        .and_then(|x| i64::from_object(x).ok());
+
+    Ok(value.unwrap_or(0).max(0) as usize)
+}
+
+/// Screen cells of the line, the way Neovim displays it
+fn cells(line: &str, tabstop: usize, widths: &mut HashMap<char, usize>) -> Result<Vec<Cell>> {
+    let mut cells = Vec::new();
+    let mut column = 0;
+
+    for (byte, char) in line.char_indices() {
+        if char == '\t' {
+            let width = tabstop - column % tabstop;
+
+            cells.extend((0..width).map(|_| Cell {
+                text: String::from(" "),
+                width: 1,
+                byte,
+            }));
+            column += width;
+            continue;
+        }
+
+        let width = match widths.get(&char) {
+            Some(width) => *width,
+            None if char.is_ascii() => 1,
+            None => {
+                let width: u32 = api::call_function("strdisplaywidth", (char.to_string(),))?;
+                widths.insert(char, width as usize);
+                width as usize
+            }
+        };
+
+        cells.push(Cell {
+            text: char.to_string(),
+            width,
+            byte,
+        });
+        column += width;
+    }
+
+    Ok(cells)
+}
+
+/// Copies the visible part of the real buffer into the overlay, flipped
+fn render(overlay: &Overlay, flip: Flip) -> Result<()> {
+    let source = &overlay.source;
+
+    if !source.is_valid() || !overlay.win.is_valid() {
+        return Ok(());
+    }
+
+    let mut win = overlay.win.clone();
+    win.set_config(&window_config(source)?)?;
+
+    let width = source.get_width()? as usize;
+    let height = source.get_height()? as usize;
+
+    let top = win_info(source, "topline")?.max(1);
+    let left = win_info(source, "leftcol")?;
+    // Columns of the text, without the number and sign columns
+    let gutter = win_info(source, "textoff")?.min(width);
+    let text_width = width - gutter;
+
+    let (row, col) = source.get_cursor()?;
+    let source_buf = source.get_buf()?;
+
+    let opts = OptionOpts::builder().buffer(source_buf.clone()).build();
+    let tabstop = api::get_option_value::<u32>("tabstop", &opts)?.max(1) as usize;
+
+    let lines: Vec<String> = source_buf
+        .get_lines(top - 1..top - 1 + height, false)?
+        .map(|x| x.to_string())
+        .collect();
+
+    let cursor_line = row.checked_sub(top).filter(|x| *x < height);
+    let mut cursor = None;
+    let mut widths = HashMap::new();
+    let mut flipped = Vec::with_capacity(height);
+
+    for index in 0..height {
+        let line = lines.get(index).map_or("", String::as_str);
+        let mut visible = Vec::new();
+        let mut column = 0;
+
+        for cell in cells(line, tabstop, &mut widths)? {
+            let start = column;
+            column += cell.width;
+
+            if start < left || column > left + text_width {
+                // A wide character cut by the edge of the window shows as blanks
+                if column > left && start < left + text_width {
+                    visible.extend((left.max(start)..column.min(left + text_width)).map(|_| {
+                        Cell {
+                            text: String::from(" "),
+                            width: 1,
+                            byte: cell.byte,
+                        }
+                    }));
+                }
+
+                continue;
+            }
+
+            visible.push(cell);
+        }
+
+        let used: usize = visible.iter().map(|x| x.width).sum();
+        let mut text = " ".repeat(text_width.saturating_sub(used));
+
+        for cell in visible.iter().rev() {
+            if cursor_line == Some(index) && cursor.is_none() && cell.byte <= col {
+                cursor = Some((text.len(), text.len() + cell.text.len()));
+            }
+
+            text.push_str(&cell.text);
+        }
+
+        text.push_str(&" ".repeat(gutter));
+        flipped.push(text);
+    }
+
+    let mut cursor_line = cursor_line;
+
+    if flip == Flip::UpsideDown {
+        flipped.reverse();
+        cursor_line = cursor_line.map(|x| height - 1 - x);
+    }
+
+    let mut buf = overlay.buf.clone();
+    let opts = OptionOpts::builder().buffer(buf.clone()).build();
+
+    api::set_option_value("modifiable", true, &opts)?;
+    buf.set_lines(.., false, flipped)?;
+    api::set_option_value("modifiable", false, &opts)?;
+
+    let namespace = api::create_namespace(GROUP);
+    buf.clear_namespace(namespace, ..)?;
+
+    if let (Some(line), Some((start, end))) = (cursor_line, cursor) {
+        buf.add_highlight(namespace, "Cursor", line, start..end)?;
+    }
+
+    Ok(())
+}
+
+impl ModeCommand for Command {
+    fn start(&self) -> Result<()> {
+        let source = api::get_current_win();
+        let opts = local_options(&source);
+
+        let wrap = api::get_option_value("wrap", &opts)?;
+        let foldenable = api::get_option_value("foldenable", &opts)?;
+
+        let buf = api::create_buf(false, true)?;
+        let win = api::open_win(&buf, false, &window_config(&source)?)?;
+
+        // Screen lines then match buffer lines, the overlay only mirrors those
+        api::set_option_value("wrap", false, &opts)?;
+        api::set_option_value("foldenable", false, &opts)?;
+
+        let overlay = Overlay {
+            buf,
+            win,
+            source,
+            wrap,
+            foldenable,
+        };
+        render(&overlay, self.flip)?;
+
+        *self.overlay.borrow_mut() = Some(overlay.clone());
+
+        let group = api::create_augroup(GROUP, &CreateAugroupOpts::builder().clear(true).build())?;
+        let flip = self.flip;
+
+        let opts = CreateAutocmdOpts::builder()
+            .group(group)
+            .callback(Function::from_fn(move |_args: AutocmdCallbackArgs| {
+                if let Err(error) = render(&overlay, flip) {
+                    Plugin::err(error.to_string().as_str());
+                }
+
+                false
+            }))
+            .build();
+
+        let events = [
+            "CursorMoved",
+            "CursorMovedI",
+            "TextChanged",
+            "TextChangedI",
+            "WinScrolled",
+            "WinResized",
+        ];
+
+        api::create_autocmd(events, &opts)?;
+
+        Ok(())
+    }
+
+    fn is_valid(&self) -> Result<bool> {
+        Ok(true)
+    }
+
+    fn stop(&self) -> Result<()> {
+        // The overlay goes first, it must never outlive the mode
+        if let Some(overlay) = self.overlay.take() {
+            if overlay.win.is_valid() {
+                overlay.win.close(true)?;
+            }
+
+            if overlay.buf.is_valid() {
+                let opts = BufDeleteOpts::builder().force(true).build();
+                overlay.buf.delete(&opts)?;
+            }
+
+            if overlay.source.is_valid() {
+                let opts = local_options(&overlay.source);
+
+                api::set_option_value("wrap", overlay.wrap, &opts)?;
+                api::set_option_value("foldenable", overlay.foldenable, &opts)?;
+            }
+        }
+
+        augroup::delete(GROUP)
+    }
+
+    fn name(&self) -> String {
+        match self.flip {
+            Flip::Mirror => String::from("Mirror"),
+            Flip::UpsideDown => String::from("Upside Down"),
+        }
+    }
+}
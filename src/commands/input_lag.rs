@@ -0,0 +1,156 @@
+use std::{
+    cell::RefCell,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+use nvim_oxi::{
+    api::{
+        self,
+        opts::{CreateAugroupOpts, CreateAutocmdOpts},
+        types::AutocmdCallbackArgs,
+    },
+    mlua::{self, Function as LuaFunction, String as LuaString, Table},
+    Function,
+};
+use rand::Rng;
+
+use crate::core::plugin::{Plugin, CONFIG};
+use crate::error::Result;
+
+use super::{augroup, ModeCommand};
+
+const NAMESPACE: &str = "nvim-chaos-lag";
+
+#[derive(Default, PartialEq, Clone, Debug)]
+pub struct Command {}
+
+struct Lag {
+    /// Keys fed back that must pass through the hook
+    replaying: usize,
+    /// Keys produced by a mapping of the key just held back, until the event loop runs again
+    is_expanding: bool,
+    /// When the last held back key is replayed, keys never overtake each other
+    last: Instant,
+}
+
+fn vim() -> mlua::Result<Table<'static>> {
+    mlua::lua().globals().get("vim")
+}
+
+/// Sets the `vim.on_key()` hook of the plugin, `None` removes it
+fn on_key(callback: Option<LuaFunction<'static>>) -> Result<()> {
+    let on_key: LuaFunction = vim()?.get("on_key")?;
+    let namespace = api::create_namespace(NAMESPACE);
+
+    on_key.call::<_, ()>((callback, namespace))?;
+
+    Ok(())
+}
+
+fn replay(lag: &Rc<RefCell<Lag>>, typed: Vec<u8>) -> mlua::Result<()> {
+    let config = &CONFIG.get().unwrap().commands.lag;
+    let lua = mlua::lua();
+
+    let jitter = rand::thread_rng().gen_range(0..=config.jitter);
+    let delay = Duration::from_millis(u64::from(config.delay + jitter));
+
+    let due = {
+        let mut lag = lag.borrow_mut();
+        lag.last = lag.last.max(Instant::now() + delay);
+        lag.last
+    };
+
+    let lag = Rc::clone(lag);
+    let callback = lua.create_function(move |lua, ()| {
+        lag.borrow_mut().replaying += 1;
+
+        let feedkeys: LuaFunction = vim()?.get::<_, Table>("api")?.get("nvim_feedkeys")?;
+        feedkeys.call::<_, ()>((lua.create_string(&typed)?, "mt", false))
+    })?;
+
+    let millis = due.saturating_duration_since(Instant::now()).as_millis();
+
+    let defer_fn: LuaFunction = vim()?.get("defer_fn")?;
+    defer_fn.call::<_, ()>((callback, millis as u64))
+}
+
+impl ModeCommand for Command {
+    fn start(&self) -> Result<()> {
+        let lag = Rc::new(RefCell::new(Lag {
+            replaying: 0,
+            is_expanding: false,
+            last: Instant::now(),
+        }));
+
+        // Returning an empty string discards the key, `nil` lets it through
+        let callback =
+            mlua::lua().create_function(move |lua, (_key, typed): (LuaString, LuaString)| {
+                let typed = typed.as_bytes();
+
+                // The replay expands the mapping again, other keys that weren't typed pass
+                if typed.is_empty() {
+                    let is_expanding = lag.borrow().is_expanding;
+                    return Ok(is_expanding.then(String::new));
+                }
+
+                {
+                    let mut lag = lag.borrow_mut();
+
+                    if lag.replaying > 0 {
+                        lag.replaying -= 1;
+                        lag.is_expanding = false;
+                        return Ok(None);
+                    }
+
+                    lag.is_expanding = true;
+                }
+
+                replay(&lag, typed.to_vec())?;
+
+                let expanded = Rc::clone(&lag);
+                let schedule: LuaFunction = vim()?.get("schedule")?;
+                schedule.call::<_, ()>(lua.create_function(move |_, ()| {
+                    expanded.borrow_mut().is_expanding = false;
+                    Ok(())
+                })?)?;
+
+                Ok(Some(String::new()))
+            })?;
+
+        on_key(Some(callback))?;
+
+        // Input must never stay hooked once Neovim quits
+        let group =
+            api::create_augroup(NAMESPACE, &CreateAugroupOpts::builder().clear(true).build())?;
+        let opts = CreateAutocmdOpts::builder()
+            .group(group)
+            .callback(Function::from_fn(|_args: AutocmdCallbackArgs| {
+                if let Err(error) = on_key(None) {
+                    Plugin::err(error.to_string().as_str());
+                }
+
+                false
+            }))
+            .build();
+
+        api::create_autocmd(["VimLeavePre"], &opts)?;
+
+        Ok(())
+    }
+
+    fn is_valid(&self) -> Result<bool> {
+        Ok(true)
+    }
+
+    fn stop(&self) -> Result<()> {
+        on_key(None)?;
+        augroup::delete(NAMESPACE)?;
+
+        Ok(())
+    }
+
+    fn name(&self) -> String {
+        String::from("Input Lag")
+    }
+}
@@ -0,0 +1,85 @@
+use std::{cell::RefCell, rc::Rc};
+
+use rand::seq::SliceRandom;
+
+use crate::core::plugin::CONFIG;
+use crate::error::Result;
+
+use super::{keymap::Keymaps, ModeCommand};
+
+const MODES: [&str; 3] = ["n", "x", "o"];
+
+#[derive(Default, Clone, Debug)]
+pub struct Command {
+    /// Key and the key it types instead, picked on the first start
+    permutation: Rc<RefCell<Vec<(String, String)>>>,
+    keymaps: Keymaps,
+}
+
+// Every shuffle is the same mode, the permutation is only its runtime state
+impl PartialEq for Command {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+/// Random permutation of every group, a group of several keys never stays in place
+fn shuffle(groups: &[Vec<String>]) -> Vec<(String, String)> {
+    let mut rng = rand::thread_rng();
+    let mut permutation = Vec::new();
+
+    for group in groups {
+        let mut shuffled = group.clone();
+        shuffled.shuffle(&mut rng);
+
+        if shuffled == *group {
+            shuffled.rotate_left(1);
+        }
+
+        permutation.extend(group.iter().cloned().zip(shuffled));
+    }
+
+    permutation
+}
+
+impl ModeCommand for Command {
+    fn start(&self) -> Result<()> {
+        let config = &CONFIG.get().unwrap().commands.shuffle;
+
+        let mut permutation = self.permutation.borrow_mut();
+
+        // Resuming keeps the permutation the streamer already got used to
+        if permutation.is_empty() {
+            *permutation = shuffle(&config.keys);
+        }
+
+        for mode in MODES {
+            for (lhs, rhs) in permutation.iter() {
+                self.keymaps.set(mode, lhs, rhs)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn is_valid(&self) -> Result<bool> {
+        Ok(true)
+    }
+
+    fn stop(&self) -> Result<()> {
+        self.keymaps.clear()
+    }
+
+    fn name(&self) -> String {
+        String::from("Keyboard Shuffle")
+    }
+
+    fn details(&self) -> Vec<String> {
+        self.permutation
+            .borrow()
+            .iter()
+            .filter(|(lhs, rhs)| lhs != rhs)
+            .map(|(lhs, rhs)| format!("{lhs} -> {rhs}"))
+            .collect()
+    }
+}
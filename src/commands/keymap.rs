@@ -0,0 +1,246 @@
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use nvim_oxi::{
+    api::{
+        self,
+        opts::SetKeymapOpts,
+        types::{LogLevel, Mode},
+    },
+    conversion::FromObject,
+    Array, Dictionary, Function, Object, String as NvimString,
+};
+
+use crate::error::Result;
+
+/// Global mapping that was in place before any mode mapped the key
+#[derive(Clone, Debug)]
+struct Mapping {
+    /// Short name of the mode, e.g. `n` or `x`
+    mode: &'static str,
+    lhs: String,
+    /// As returned by `nvim_get_keymap()`, `None` if the key wasn't mapped
+    previous: Option<Dictionary>,
+}
+
+/// What a mode maps a key to
+#[derive(Clone, Debug)]
+enum Rhs {
+    Keys(String),
+    /// Warning shown instead of running the key
+    Warn(String),
+}
+
+/// Modes mapping a key, the last one is the mapping in place
+struct Key {
+    original: Mapping,
+    owners: Vec<(usize, Rhs)>,
+}
+
+thread_local! {
+    /// Keys mapped by running modes, by short mode name and key
+    static KEYS: RefCell<HashMap<(&'static str, String), Key>> = RefCell::default();
+}
+
+/// Mappings of a mode, shared by its clones
+///
+/// Modes can map the same key, stopping one puts back the mapping of the
+/// mode that is still running, the original one once every mode stopped.
+#[derive(Default, Clone, Debug)]
+pub struct Keymaps {
+    keys: Rc<RefCell<Vec<(&'static str, String)>>>,
+}
+
+fn api_mode(mode: &str) -> Mode {
+    match mode {
+        "x" => Mode::Visual,
+        "s" => Mode::Select,
+        "o" => Mode::OperatorPending,
+        "i" => Mode::Insert,
+        _ => Mode::Normal,
+    }
+}
+
+/// Non-recursive global mapping
+fn set(mode: &str, lhs: &str, rhs: &Rhs) -> Result<()> {
+    let mut opts = SetKeymapOpts::builder();
+    opts.noremap(true);
+
+    let keys = match rhs {
+        Rhs::Keys(keys) => keys.as_str(),
+        Rhs::Warn(message) => {
+            let message = message.clone();
+            opts.callback(Function::from_fn(move |()| {
+                let mut opts = Dictionary::new();
+                opts.insert("title", "Chaos Neovim");
+
+                let _ = api::notify(&message, LogLevel::Warn, &opts);
+            }));
+
+            ""
+        }
+    };
+
+    api::set_keymap(api_mode(mode), lhs, keys, &opts.build())?;
+
+    Ok(())
+}
+
+/// Saves the global mappings of every key in every mode
+fn snapshot(modes: &[&'static str], keys: &[&str]) -> Result<Vec<Mapping>> {
+    let mut mappings = Vec::new();
+
+    for mode in modes {
+        // Unlike `maparg()`, this never returns a buffer-local mapping hiding the global one
+        let global: Array = api::call_function("nvim_get_keymap", (*mode,))?;
+        let global: Vec<Dictionary> = global
+            .into_iter()
+            .filter_map(|x| Dictionary::from_object(x).ok())
+            .collect();
+
+        for lhs in keys {
+            let raw: NvimString =
+                api::call_function("nvim_replace_termcodes", (*lhs, true, true, true))?;
+
+            let previous = global
+                .iter()
+                .find(|x| {
+                    x.get("lhsraw")
+                        .cloned()
+                        .map(NvimString::from_object)
+                        .is_some_and(|x| x.is_ok_and(|x| x == raw))
+                })
+                .cloned();
+
+            mappings.push(Mapping {
+                mode: *mode,
+                lhs: String::from(*lhs),
+                previous,
+            });
+        }
+    }
+
+    Ok(mappings)
+}
+
+/// Puts the saved mapping back, a key that wasn't mapped is unmapped
+fn put_back(mapping: &Mapping) -> Result<()> {
+    match &mapping.previous {
+        Some(previous) => {
+            api::call_function::<_, Object>("mapset", (mapping.mode, false, previous.clone()))?;
+        }
+        None => {
+            // The mapping may be gone already, e.g. removed with `:unmap`
+            let current = snapshot(&[mapping.mode], &[&mapping.lhs])?;
+
+            if current.iter().any(|x| x.previous.is_some()) {
+                api::del_keymap(api_mode(mapping.mode), &mapping.lhs)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// What to map a key to once a mode gave it back
+enum Next {
+    /// The mode wasn't the last one mapping the key
+    Keep,
+    /// Mapping of the mode now last
+    Set(Rhs),
+    /// No mode maps the key anymore
+    Restore(Mapping),
+}
+
+fn release(
+    keys: &mut HashMap<(&'static str, String), Key>,
+    key: &(&'static str, String),
+    owner: usize,
+) -> Next {
+    let Some(entry) = keys.get_mut(key) else {
+        return Next::Keep;
+    };
+
+    let Some(index) = entry.owners.iter().position(|(x, _)| *x == owner) else {
+        return Next::Keep;
+    };
+
+    entry.owners.remove(index);
+
+    if index < entry.owners.len() {
+        return Next::Keep;
+    }
+
+    match entry.owners.last() {
+        Some((_, rhs)) => Next::Set(rhs.clone()),
+        None => Next::Restore(keys.remove(key).unwrap().original),
+    }
+}
+
+impl Keymaps {
+    fn owner(&self) -> usize {
+        Rc::as_ptr(&self.keys) as usize
+    }
+
+    /// Maps the key over the mappings of the user and of other modes
+    pub fn set(&self, mode: &'static str, lhs: &str, rhs: &str) -> Result<()> {
+        self.push(mode, lhs, Rhs::Keys(String::from(rhs)))
+    }
+
+    /// Maps the key to a warning instead of its action
+    pub fn warn(&self, mode: &'static str, lhs: &str, message: &str) -> Result<()> {
+        self.push(mode, lhs, Rhs::Warn(String::from(message)))
+    }
+
+    fn push(&self, mode: &'static str, lhs: &str, rhs: Rhs) -> Result<()> {
+        let key = (mode, String::from(lhs));
+        let is_new = KEYS.with_borrow(|keys| !keys.contains_key(&key));
+
+        if is_new {
+            let original = snapshot(&[mode], &[lhs])?.remove(0);
+            let owners = Vec::new();
+
+            KEYS.with_borrow_mut(|keys| keys.insert(key.clone(), Key { original, owners }));
+        }
+
+        let owner = self.owner();
+
+        KEYS.with_borrow_mut(|keys| {
+            let owners = &mut keys.get_mut(&key).unwrap().owners;
+
+            owners.retain(|(x, _)| *x != owner);
+            owners.push((owner, rhs.clone()));
+        });
+
+        let mut owned = self.keys.borrow_mut();
+
+        if !owned.contains(&key) {
+            owned.push(key);
+        }
+
+        set(mode, lhs, &rhs)
+    }
+
+    /// Gives back every key of the mode, the first error is returned once all were handled
+    pub fn clear(&self) -> Result<()> {
+        let owner = self.owner();
+        let mut result = Ok(());
+
+        for (mode, lhs) in self.keys.take() {
+            let key = (mode, lhs);
+
+            let next = KEYS.with_borrow_mut(|keys| release(keys, &key, owner));
+
+            let applied = match next {
+                Next::Keep => Ok(()),
+                Next::Set(rhs) => set(mode, &key.1, &rhs),
+                Next::Restore(original) => put_back(&original),
+            };
+
+            if let Err(error) = applied {
+                result = result.and(Err(error));
+            }
+        }
+
+        result
+    }
+}
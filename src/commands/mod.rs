@@ -1,9 +1,26 @@
 use crate::error::Result;
-pub(crate) use color_scheme::{Background, Command as ColorSchemeCommand};
+pub(crate) use ban_key::{Command as BanKeyCommand, NEVER_BANNED};
+pub(crate) use color_scheme::{
+    find as find_color_scheme, installed as installed_color_schemes, Background,
+    Command as ColorSchemeCommand,
+};
 use enum_dispatch::enum_dispatch;
+pub(crate) use flip::{Command as FlipCommand, Flip};
+pub(crate) use input_lag::Command as InputLagCommand;
+pub(crate) use keyboard_shuffle::Command as KeyboardShuffleCommand;
+pub(crate) use typo::Command as TypoCommand;
 pub(crate) use vim_motions_hell::Command as VimMotionsHellCommand;
 
+pub(crate) mod args;
+mod augroup;
+mod ban_key;
 mod color_scheme;
+mod flip;
+mod input_lag;
+mod keyboard_shuffle;
+mod keymap;
+pub(crate) mod registry;
+mod typo;
 mod vim_motions_hell;
 
 #[enum_dispatch(Mode)]
@@ -15,6 +32,11 @@ pub trait ModeCommand {
     fn name(&self) -> String;
 
     fn is_valid(&self) -> Result<bool>;
+
+    /// Extra lines shown under the mode in the status window
+    fn details(&self) -> Vec<String> {
+        Vec::new()
+    }
 }
 
 #[enum_dispatch]
@@ -23,6 +45,16 @@ pub enum Mode {
     VimMotionsHellCommand,
 
     ColorSchemeCommand,
+
+    KeyboardShuffleCommand,
+
+    InputLagCommand,
+
+    TypoCommand,
+
+    BanKeyCommand,
+
+    FlipCommand,
 }
 
 #[derive(Clone, PartialEq, Eq)]
@@ -30,4 +62,38 @@ pub enum ModeType {
     VimMotionsHellType,
 
     ColorSchemeType,
+
+    KeyboardShuffleType,
+
+    InputLagType,
+
+    TypoType,
+
+    /// Every banned key is a mode of its own
+    BannedKeyType(String),
+
+    FlipType,
+}
+
+impl ModeType {
+    /// Key of the mode in the config, same as the command producing it
+    pub fn key(&self) -> &'static str {
+        match self {
+            ModeType::VimMotionsHellType => "hell",
+            ModeType::ColorSchemeType => "colorscheme",
+            ModeType::KeyboardShuffleType => "shuffle",
+            ModeType::InputLagType => "lag",
+            ModeType::TypoType => "typo",
+            ModeType::BannedKeyType(_) => "ban",
+            ModeType::FlipType => "flip",
+        }
+    }
+}
+
+/// Mode ready to be started
+#[derive(Clone)]
+pub struct ModeSpec {
+    pub mode: Mode,
+    pub mode_type: ModeType,
+    pub seconds: u32,
 }
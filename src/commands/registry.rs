@@ -0,0 +1,323 @@
+use crate::core::config::Config;
+use crate::error::{Error, Result};
+
+use super::{
+    args::{ArgError, ArgKind, ArgSpec, Args},
+    find_color_scheme, Background, BanKeyCommand, ColorSchemeCommand, Flip, FlipCommand,
+    InputLagCommand, KeyboardShuffleCommand, ModeSpec, ModeType, TypoCommand,
+    VimMotionsHellCommand, NEVER_BANNED,
+};
+
+/// Builds the mode from the command arguments
+pub type ModeBuilder = fn(&Args, &Config) -> Result<ModeSpec>;
+
+/// What the command does once it's matched
+#[derive(Clone, Copy)]
+pub enum Action {
+    Mode(ModeBuilder),
+    Message,
+    ColorSchemes,
+    Help,
+    Vote,
+}
+
+#[derive(Clone)]
+pub struct CommandDef {
+    /// Key of the command in the config, used by channels, cooldowns and permissions
+    pub key: &'static str,
+    /// Names the command is triggered by, the first one is shown to viewers
+    pub triggers: Vec<String>,
+    pub args: Vec<ArgSpec>,
+    /// Duration of the produced mode, shown in the help
+    pub duration: Option<u32>,
+    pub action: Action,
+}
+
+impl CommandDef {
+    pub fn name(&self) -> &str {
+        self.triggers.first().map_or(self.key, String::as_str)
+    }
+
+    pub fn is_mode(&self) -> bool {
+        matches!(self.action, Action::Mode(_))
+    }
+
+    /// Mode left to the polls when voting is on, modes taking a viewer's pick stay direct
+    pub fn is_voted(&self) -> bool {
+        self.is_mode() && !self.args.iter().any(|x| x.required)
+    }
+
+    pub fn usage(&self) -> String {
+        let mut usage = String::from(self.name());
+
+        for arg in &self.args {
+            usage.push(' ');
+            usage.push_str(&arg.usage());
+        }
+
+        if let Some(duration) = self.duration {
+            usage.push_str(&format!(" ({})", format_duration(duration)));
+        }
+
+        usage
+    }
+}
+
+/// Command matched in a chat message
+#[derive(Debug, Clone)]
+pub struct Invocation {
+    pub key: &'static str,
+    /// Arguments, or why they don't fit the command
+    pub args: std::result::Result<Args, ArgError>,
+}
+
+/// Every chat command the plugin understands
+pub struct Registry {
+    commands: Vec<CommandDef>,
+    ignore_case: bool,
+}
+
+impl Registry {
+    pub fn new(config: &Config) -> Self {
+        let commands = &config.commands;
+
+        const BACKGROUNDS: &[&str] = &["dark", "light"];
+        const FLIPS: &[&str] = &["mirror", "upside"];
+
+        let mut defs = vec![
+            CommandDef {
+                key: "message",
+                triggers: commands.message.0.clone(),
+                args: vec![ArgSpec::new("text", ArgKind::Text)],
+                duration: None,
+                action: Action::Message,
+            },
+            CommandDef {
+                key: "colorscheme",
+                triggers: commands.colorscheme.name.0.clone(),
+                args: vec![
+                    ArgSpec::new("name", ArgKind::Word),
+                    ArgSpec::new("background", ArgKind::Choice(BACKGROUNDS)).optional(),
+                    ArgSpec::new("duration", ArgKind::Duration).optional(),
+                ],
+                duration: Some(commands.colorscheme.duration),
+                action: Action::Mode(colorscheme_mode),
+            },
+            CommandDef {
+                key: "colorschemes",
+                triggers: commands.colorscheme.list.0.clone(),
+                args: vec![ArgSpec::new("page", ArgKind::Integer { min: 1, max: 100 }).optional()],
+                duration: None,
+                action: Action::ColorSchemes,
+            },
+            CommandDef {
+                key: "hell",
+                triggers: commands.hell.name.0.clone(),
+                args: vec![ArgSpec::new("duration", ArgKind::Duration).optional()],
+                duration: Some(commands.hell.duration),
+                action: Action::Mode(hell_mode),
+            },
+            CommandDef {
+                key: "shuffle",
+                triggers: commands.shuffle.name.0.clone(),
+                args: vec![ArgSpec::new("duration", ArgKind::Duration).optional()],
+                duration: Some(commands.shuffle.duration),
+                action: Action::Mode(shuffle_mode),
+            },
+            CommandDef {
+                key: "lag",
+                triggers: commands.lag.name.0.clone(),
+                args: vec![ArgSpec::new("duration", ArgKind::Duration).optional()],
+                duration: Some(commands.lag.duration),
+                action: Action::Mode(lag_mode),
+            },
+            CommandDef {
+                key: "typo",
+                triggers: commands.typo.name.0.clone(),
+                args: vec![ArgSpec::new("duration", ArgKind::Duration).optional()],
+                duration: Some(commands.typo.duration),
+                action: Action::Mode(typo_mode),
+            },
+            CommandDef {
+                key: "ban",
+                triggers: commands.ban.name.0.clone(),
+                args: vec![
+                    ArgSpec::new("key", ArgKind::Word),
+                    ArgSpec::new("duration", ArgKind::Duration).optional(),
+                ],
+                duration: Some(commands.ban.duration),
+                action: Action::Mode(ban_mode),
+            },
+            CommandDef {
+                key: "flip",
+                triggers: commands.flip.name.0.clone(),
+                args: vec![
+                    ArgSpec::new("flip", ArgKind::Choice(FLIPS)).optional(),
+                    ArgSpec::new("duration", ArgKind::Duration).optional(),
+                ],
+                duration: Some(commands.flip.duration),
+                action: Action::Mode(flip_mode),
+            },
+            CommandDef {
+                key: "help",
+                triggers: commands.help.0.clone(),
+                args: Vec::new(),
+                duration: None,
+                action: Action::Help,
+            },
+        ];
+
+        if let Some(voting) = &config.voting {
+            let max = i64::try_from(voting.options).unwrap_or(i64::MAX);
+
+            defs.push(CommandDef {
+                key: "vote",
+                triggers: commands.vote.0.clone(),
+                args: vec![ArgSpec::new("option", ArgKind::Integer { min: 1, max })],
+                duration: None,
+                action: Action::Vote,
+            });
+        }
+
+        Self {
+            commands: defs,
+            ignore_case: commands.ignore_case,
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&CommandDef> {
+        self.commands.iter().find(|x| x.key == key)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &CommandDef> {
+        self.commands.iter()
+    }
+
+    fn matches(&self, name: &str, trigger: &str) -> bool {
+        if self.ignore_case {
+            name.to_lowercase() == trigger.to_lowercase()
+        } else {
+            name == trigger
+        }
+    }
+
+    /// Matches the chat message against the triggers of every command
+    pub fn parse(&self, text: &str) -> Option<Invocation> {
+        let text = text.trim();
+        let (trigger, rest) = text.split_once(' ').unwrap_or((text, ""));
+
+        let command = self
+            .commands
+            .iter()
+            .find(|x| x.triggers.iter().any(|x| self.matches(x, trigger)))?;
+
+        Some(Invocation {
+            key: command.key,
+            args: Args::parse(&command.args, rest),
+        })
+    }
+}
+
+fn colorscheme_mode(args: &Args, config: &Config) -> Result<ModeSpec> {
+    let name = args.text("name").unwrap_or_default();
+
+    let Some(colorscheme) = find_color_scheme(name)? else {
+        return Err(Error::Rejected(format!(
+            "unknown colorscheme {name}, see {}",
+            config.commands.colorscheme.list.first()
+        )));
+    };
+
+    let background = args
+        .text("background")
+        .and_then(|x| x.parse().ok())
+        .unwrap_or(Background::Default);
+
+    Ok(ModeSpec {
+        mode: ColorSchemeCommand {
+            colorscheme,
+            background,
+        }
+        .into(),
+        mode_type: ModeType::ColorSchemeType,
+        seconds: config.commands.colorscheme.duration,
+    })
+}
+
+fn hell_mode(_args: &Args, config: &Config) -> Result<ModeSpec> {
+    Ok(ModeSpec {
+        mode: VimMotionsHellCommand::default().into(),
+        mode_type: ModeType::VimMotionsHellType,
+        seconds: config.commands.hell.duration,
+    })
+}
+
+fn shuffle_mode(_args: &Args, config: &Config) -> Result<ModeSpec> {
+    Ok(ModeSpec {
+        mode: KeyboardShuffleCommand::default().into(),
+        mode_type: ModeType::KeyboardShuffleType,
+        seconds: config.commands.shuffle.duration,
+    })
+}
+
+fn lag_mode(_args: &Args, config: &Config) -> Result<ModeSpec> {
+    Ok(ModeSpec {
+        mode: InputLagCommand::default().into(),
+        mode_type: ModeType::InputLagType,
+        seconds: config.commands.lag.duration,
+    })
+}
+
+fn typo_mode(_args: &Args, config: &Config) -> Result<ModeSpec> {
+    Ok(ModeSpec {
+        mode: TypoCommand::default().into(),
+        mode_type: ModeType::TypoType,
+        seconds: config.commands.typo.duration,
+    })
+}
+
+fn ban_mode(args: &Args, config: &Config) -> Result<ModeSpec> {
+    let key = args.text("key").unwrap_or_default();
+    let keys: Vec<&str> = config
+        .commands
+        .ban
+        .keys
+        .iter()
+        .map(String::as_str)
+        .filter(|x| !NEVER_BANNED.contains(x))
+        .collect();
+
+    if !keys.contains(&key) {
+        return Err(Error::Rejected(format!(
+            "{key} can't be banned, pick one of {}",
+            keys.join(" ")
+        )));
+    }
+
+    Ok(ModeSpec {
+        mode: BanKeyCommand::new(String::from(key)).into(),
+        mode_type: ModeType::BannedKeyType(String::from(key)),
+        seconds: config.commands.ban.duration,
+    })
+}
+
+fn flip_mode(args: &Args, config: &Config) -> Result<ModeSpec> {
+    let flip = match args.text("flip") {
+        Some("upside") => Flip::UpsideDown,
+        _ => Flip::Mirror,
+    };
+
+    Ok(ModeSpec {
+        mode: FlipCommand::new(flip).into(),
+        mode_type: ModeType::FlipType,
+        seconds: config.commands.flip.duration,
+    })
+}
+
+pub fn format_duration(seconds: u32) -> String {
+    match (seconds / 60, seconds % 60) {
+        (0, seconds) => format!("{seconds}s"),
+        (minutes, 0) => format!("{minutes}m"),
+        (minutes, seconds) => format!("{minutes}m{seconds}s"),
+    }
+}
@@ -0,0 +1,98 @@
+use std::{cell::RefCell, rc::Rc};
+
+use nvim_oxi::{
+    api::{
+        self,
+        opts::{CreateAugroupOpts, CreateAutocmdOpts},
+        types::AutocmdCallbackArgs,
+    },
+    Function,
+};
+use rand::Rng;
+
+use crate::core::plugin::{Plugin, CONFIG};
+use crate::error::Result;
+
+use super::{augroup, ModeCommand};
+
+const GROUP: &str = "nvim-chaos-typo";
+
+#[derive(Default, PartialEq, Clone, Debug)]
+pub struct Command {}
+
+/// Rewrites the typed character, `held` is a character waiting to be swapped with the next one
+fn inject(held: &RefCell<Option<String>>) -> Result<()> {
+    let config = &CONFIG.get().unwrap().commands.typo;
+    let mut rng = rand::thread_rng();
+
+    let char: String = api::get_vvar("char")?;
+    let previous = held.borrow_mut().take();
+
+    // The edits go through `v:char`, so they are part of the keystroke's undo block
+    let typed = if let Some(previous) = previous {
+        format!("{char}{previous}")
+    } else if rng.gen_bool(config.rate.clamp(0.0, 1.0)) {
+        match rng.gen_range(0..3) {
+            0 => {
+                *held.borrow_mut() = Some(char);
+                String::new()
+            }
+            1 => char.repeat(2),
+            _ => String::new(),
+        }
+    } else {
+        return Ok(());
+    };
+
+    api::set_vvar("char", typed)?;
+
+    Ok(())
+}
+
+impl ModeCommand for Command {
+    fn start(&self) -> Result<()> {
+        let group = api::create_augroup(GROUP, &CreateAugroupOpts::builder().clear(true).build())?;
+        let held = Rc::new(RefCell::new(None));
+
+        let inserted = Rc::clone(&held);
+        let opts = CreateAutocmdOpts::builder()
+            .group(group)
+            .callback(Function::from_fn(move |_args: AutocmdCallbackArgs| {
+                if let Err(error) = inject(&inserted) {
+                    Plugin::err(error.to_string().as_str());
+                }
+
+                false
+            }))
+            .build();
+
+        api::create_autocmd(["InsertCharPre"], &opts)?;
+
+        // A character held for a swap is dropped when the insert ends
+        let opts = CreateAutocmdOpts::builder()
+            .group(group)
+            .callback(Function::from_fn(move |_args: AutocmdCallbackArgs| {
+                *held.borrow_mut() = None;
+                false
+            }))
+            .build();
+
+        api::create_autocmd(["InsertLeave"], &opts)?;
+
+        Ok(())
+    }
+
+    fn is_valid(&self) -> Result<bool> {
+        Ok(true)
+    }
+
+    fn stop(&self) -> Result<()> {
+        augroup::delete(GROUP)?;
+
+        Ok(())
+    }
+
+    fn name(&self) -> String {
+        String::from("Typos")
+    }
+}
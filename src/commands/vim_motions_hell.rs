@@ -1,23 +1,44 @@
-use nvim_oxi::api;
-
+use crate::core::plugin::CONFIG;
 use crate::error::Result;
 
-use super::ModeCommand;
+use super::{keymap::Keymaps, ModeCommand};
+
+const MODES: [&str; 3] = ["n", "x", "o"];
+
+const INSERT_ARROWS: [(&str, &str); 4] = [
+    ("<Left>", "<Right>"),
+    ("<Right>", "<Left>"),
+    ("<Up>", "<Down>"),
+    ("<Down>", "<Up>"),
+];
 
-#[derive(Default, PartialEq, Clone, Debug)]
-pub struct Command {}
+#[derive(Default, Clone, Debug)]
+pub struct Command {
+    keymaps: Keymaps,
+}
+
+// Every Vim Motions Hell is the same mode, the keymaps are only its runtime state
+impl PartialEq for Command {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
 
 impl ModeCommand for Command {
     fn start(&self) -> Result<()> {
-        api::command("noremap l h")?;
-        api::command("noremap k j")?;
-        api::command("noremap j k")?;
-        api::command("noremap h l")?;
+        let config = &CONFIG.get().unwrap().commands.hell;
+
+        for mode in MODES {
+            for (lhs, rhs) in &config.mappings {
+                self.keymaps.set(mode, lhs, rhs)?;
+            }
+        }
 
-        api::command("noremap w b")?;
-        api::command("noremap b w")?;
-        api::command("noremap e ge")?;
-        api::command("noremap ge e")?;
+        if config.insert_arrows {
+            for (lhs, rhs) in INSERT_ARROWS {
+                self.keymaps.set("i", lhs, rhs)?;
+            }
+        }
 
         Ok(())
     }
@@ -27,17 +48,7 @@ impl ModeCommand for Command {
     }
 
     fn stop(&self) -> Result<()> {
-        api::command("noremap l l")?;
-        api::command("noremap k k")?;
-        api::command("noremap j j")?;
-        api::command("noremap h h")?;
-
-        api::command("noremap w w")?;
-        api::command("noremap b b")?;
-        api::command("noremap e e")?;
-        api::command("noremap ge ge")?;
-
-        Ok(())
+        self.keymaps.clear()
     }
 
     fn name(&self) -> String {
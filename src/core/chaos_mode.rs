@@ -7,6 +7,12 @@ use nvim_oxi::api::{
 
 use crate::commands::{Mode, ModeCommand, ModeType};
 
+use super::{
+    config::{CooldownConfig, ModeConfig, Policy},
+    cooldown::Cooldowns,
+    voting::Voting,
+};
+
 use crate::error::Result;
 
 #[derive(Clone)]
@@ -16,11 +22,48 @@ pub struct ModeState {
     pub seconds: u32,
 }
 
+/// Outcome of triggering a mode
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Activation {
+    Started,
+    /// Seconds added to the active mode, zero once it reached the cap
+    Extended {
+        active: String,
+        added: u32,
+    },
+    Queued,
+    /// The queue of the mode type is full
+    QueueFull,
+    Ignored,
+    /// The mode can't be started, e.g. the color scheme isn't installed
+    Invalid,
+}
+
+impl Activation {
+    pub fn is_used(&self) -> bool {
+        !matches!(
+            self,
+            Activation::Extended { added: 0, .. }
+                | Activation::QueueFull
+                | Activation::Ignored
+                | Activation::Invalid
+        )
+    }
+}
+
 #[derive(Clone)]
 pub struct State {
     pub buf: Buffer,
     pub win: Option<Window>,
     pub commands: Vec<ModeState>,
+    /// Modes waiting for the active mode of their type to finish
+    pub queue: Vec<ModeState>,
+    pub cooldowns: Cooldowns,
+    pub voting: Voting,
+    /// Timers are frozen while paused
+    pub is_paused: bool,
+    /// Seconds until commands are accepted again after a panic
+    pub lockout: u32,
 }
 
 impl Default for State {
@@ -29,6 +72,11 @@ impl Default for State {
             buf: 0.into(),
             win: None,
             commands: Vec::default(),
+            queue: Vec::default(),
+            cooldowns: Cooldowns::default(),
+            voting: Voting::default(),
+            is_paused: false,
+            lockout: 0,
         }
     }
 }
@@ -40,35 +88,203 @@ impl State {
         Ok(())
     }
 
-    pub fn set_mode(&mut self, mode: Mode, mode_type: ModeType, seconds: u32) -> Result<()> {
-        if mode.is_valid()? {
-            self.commands.retain(|x| x.mode_type != mode_type);
+    /// Starts the mode, or applies the policy if a mode of the same type is active
+    pub fn set_mode(
+        &mut self,
+        mode: Mode,
+        mode_type: ModeType,
+        seconds: u32,
+        config: &ModeConfig,
+    ) -> Result<Activation> {
+        if !mode.is_valid()? {
+            return Ok(Activation::Invalid);
+        }
+
+        let active = self.commands.iter_mut().find(|x| x.mode_type == mode_type);
+
+        let activation = match (active, config.policy) {
+            (None, _) | (Some(_), Policy::Replace) => {
+                // Stopped first so the new mode starts from a clean editor, e.g. its own keymaps
+                for command in self.commands.iter().filter(|x| x.mode_type == mode_type) {
+                    command.mode.stop()?;
+                }
 
-            mode.start()?;
-            self.commands.push(ModeState {
-                mode,
-                mode_type,
-                seconds,
-            });
+                self.commands.retain(|x| x.mode_type != mode_type);
+
+                mode.start()?;
+                self.commands.push(ModeState {
+                    mode,
+                    mode_type,
+                    seconds,
+                });
+
+                Activation::Started
+            }
+            (Some(active), Policy::Extend) => {
+                let cap = config.cap.unwrap_or(u32::MAX).max(active.seconds);
+                let extended = active.seconds.saturating_add(seconds).min(cap);
+                let added = extended - active.seconds;
+
+                active.seconds = extended;
+                Activation::Extended {
+                    active: active.mode.name(),
+                    added,
+                }
+            }
+            (Some(_), Policy::Queue)
+                if self
+                    .queue
+                    .iter()
+                    .filter(|x| x.mode_type == mode_type)
+                    .count()
+                    >= config.queue =>
+            {
+                return Ok(Activation::QueueFull);
+            }
+            (Some(_), Policy::Queue) => {
+                self.queue.push(ModeState {
+                    mode,
+                    mode_type,
+                    seconds,
+                });
+                Activation::Queued
+            }
+            (Some(_), Policy::Ignore) => return Ok(Activation::Ignored),
+        };
+
+        self.update()?;
+
+        Ok(activation)
+    }
 
-            self.update()?;
+    /// Starts the first queued mode of every type that is no longer active
+    fn start_queued(&mut self) -> Result<()> {
+        let mut result = Ok(());
+        let mut index = 0;
+
+        while index < self.queue.len() {
+            let mode_type = &self.queue[index].mode_type;
+
+            if self.commands.iter().any(|x| &x.mode_type == mode_type) {
+                index += 1;
+                continue;
+            }
+
+            let next = self.queue.remove(index);
+
+            // The mode may have become invalid while waiting, e.g. an uninstalled color scheme
+            let started = match next.mode.is_valid() {
+                Ok(true) => next.mode.start().map(|()| true),
+                other => other,
+            };
+
+            // A failing mode leaves the queue, the other types still start
+            match started {
+                Ok(true) => self.commands.push(next),
+                Ok(false) => {}
+                Err(error) => result = result.and(Err(error)),
+            }
         }
 
+        result
+    }
+
+    pub fn start_cooldown(
+        &mut self,
+        command: &str,
+        name: &str,
+        user: &str,
+        config: &CooldownConfig,
+    ) -> Result<()> {
+        self.cooldowns.start(command, name, user, config);
+        self.update()?;
+
         Ok(())
     }
 
-    pub fn tick(&mut self) -> Result<()> {
-        let commands = self.commands.iter();
+    /// Freezes the timers, `stop_modes` reverts the active modes until resumed
+    pub fn pause(&mut self, stop_modes: bool) -> Result<()> {
+        if self.is_paused {
+            return Ok(());
+        }
 
-        for command in commands {
-            if command.seconds == 0 {
+        if stop_modes {
+            for command in &self.commands {
                 command.mode.stop()?;
             }
         }
 
+        self.is_paused = true;
+        self.update()?;
+
+        Ok(())
+    }
+
+    /// Unfreezes the timers, `start_modes` applies the active modes again
+    pub fn resume(&mut self, start_modes: bool) -> Result<()> {
+        if !self.is_paused {
+            return Ok(());
+        }
+
+        if start_modes {
+            for command in &self.commands {
+                command.mode.start()?;
+            }
+        }
+
+        self.is_paused = false;
+        self.update()?;
+
+        Ok(())
+    }
+
+    /// Stops every mode at once and refuses commands for `lockout` seconds,
+    /// `stop_modes` tells whether pausing already stopped them
+    pub fn panic(&mut self, lockout: u32, stop_modes: bool) -> Result<()> {
+        let mut result = Ok(());
+        let is_stopped = self.is_paused && stop_modes;
+
+        // A failing mode must not keep the others running
+        for command in self.commands.drain(..).filter(|_| !is_stopped) {
+            if let Err(error) = command.mode.stop() {
+                result = result.and(Err(error));
+            }
+        }
+
+        // Nothing is left to show, the float stays closed during the lockout
+        self.queue.clear();
+        self.cooldowns = Cooldowns::default();
+        self.voting = Voting::default();
+        self.is_paused = false;
+        self.lockout = lockout;
+
+        self.close_win()?;
+
+        result
+    }
+
+    pub fn tick(&mut self) -> Result<()> {
+        self.lockout = self.lockout.saturating_sub(1);
+
+        // Commands other than modes are still accepted while paused
+        self.cooldowns.tick();
+
+        if self.is_paused {
+            return self.update();
+        }
+
+        let mut result = Ok(());
+
+        // A mode failing to stop is dropped anyway, retrying every second wouldn't help
+        for command in self.commands.iter().filter(|x| x.seconds == 0) {
+            if let Err(error) = command.mode.stop() {
+                result = result.and(Err(error));
+            }
+        }
+
         self.commands.retain(|x| x.seconds != 0);
 
-        let count = self.commands.len();
+        let result = result.and(self.start_queued());
 
         let commands = self.commands.iter_mut();
 
@@ -80,30 +296,50 @@ impl State {
             }
         }
 
-        if count == 0 {
-            self.close_win()?;
-        } else {
-            self.update()?;
-        }
+        self.update()?;
 
-        Ok(())
+        result
     }
 
-    fn update(&mut self) -> Result<()> {
-        let lines: Vec<String> = self
-            .commands
-            .iter_mut()
-            .map(|x| {
-                let seconds = x.seconds;
+    pub fn update(&mut self) -> Result<()> {
+        let modes = self.commands.iter().flat_map(|x| {
+            let details = x
+                .mode
+                .details()
+                .into_iter()
+                .map(|line| format!("         {line}  "));
 
-                let minutes = seconds / 60;
-                let seconds = seconds % 60;
+            std::iter::once(format_line(x.seconds, &x.mode.name())).chain(details)
+        });
 
-                let mode = format!("  {:0>2}:{:0>2}  {}  ", minutes, seconds, x.mode.name());
+        let queue = self
+            .queue
+            .iter()
+            .map(|x| format_line(x.seconds, &format!("{} (queued)", x.mode.name())));
 
-                mode
-            })
-            .collect();
+        let cooldowns = self
+            .cooldowns
+            .global()
+            .map(|x| format_line(x.seconds, &format!("{} cooldown", x.name)));
+
+        let mut lines: Vec<String> = modes.chain(queue).chain(cooldowns).collect();
+
+        if self.is_paused {
+            lines.insert(0, String::from("  Paused  "));
+        }
+
+        if let Some(poll) = &self.voting.poll {
+            if !lines.is_empty() {
+                lines.push(String::new());
+            }
+
+            lines.push(format_line(poll.seconds, "Vote"));
+            lines.extend(poll.lines());
+        }
+
+        if lines.is_empty() {
+            return self.close_win();
+        }
 
         let width: u32 = lines
             .clone()
@@ -118,8 +354,6 @@ impl State {
             .try_into()
             .unwrap();
 
-        let mut lines: Vec<String> = lines;
-
         lines.insert(0, String::new());
         lines.push(String::new());
 
@@ -184,3 +418,10 @@ impl State {
         Ok(())
     }
 }
+
+fn format_line(seconds: u32, text: &str) -> String {
+    let minutes = seconds / 60;
+    let seconds = seconds % 60;
+
+    format!("  {:0>2}:{:0>2}  {}  ", minutes, seconds, text)
+}
@@ -1,23 +1,65 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+
 use crate::core::config::error::Error;
 
 use nvim_oxi::{serde::Deserializer, Object, ObjectKind};
 use serde::Deserialize;
 
+/// Names a command is triggered by, a single string or a list of aliases
+#[derive(Deserialize, Clone, Debug)]
+#[serde(from = "OneOrMany")]
+pub struct Triggers(pub Vec<String>);
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum OneOrMany {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl From<OneOrMany> for Triggers {
+    fn from(value: OneOrMany) -> Self {
+        match value {
+            OneOrMany::One(name) => Triggers(vec![name]),
+            OneOrMany::Many(names) => Triggers(names),
+        }
+    }
+}
+
+impl From<&str> for Triggers {
+    fn from(name: &str) -> Self {
+        Triggers(vec![String::from(name)])
+    }
+}
+
+impl Triggers {
+    /// Name shown to viewers
+    pub fn first(&self) -> &str {
+        self.0.first().map_or("", String::as_str)
+    }
+}
+
 #[derive(Deserialize, Clone, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct ColorSchemeCommand {
     #[serde(default = "default_colorscheme_command_name")]
-    pub name: String,
+    pub name: Triggers,
     #[serde(default = "default_colorscheme_command_duration")]
     pub duration: u32,
     #[serde(default = "default_colorscheme_name")]
     pub default: String,
     #[serde(default = "default_background")]
     pub background: String,
+    /// Name of the command listing the installed color schemes
+    #[serde(default = "default_colorscheme_list_command_name")]
+    pub list: Triggers,
+    #[serde(default = "default_colorscheme_page_size")]
+    pub page_size: usize,
 }
 
-fn default_colorscheme_command_name() -> String {
-    String::from("!colorscheme")
+fn default_colorscheme_command_name() -> Triggers {
+    Triggers::from("!colorscheme")
 }
 
 fn default_colorscheme_command_duration() -> u32 {
@@ -34,6 +76,14 @@ fn default_background() -> String {
     String::from("dark")
 }
 
+fn default_colorscheme_list_command_name() -> Triggers {
+    Triggers::from("!colorschemes")
+}
+
+fn default_colorscheme_page_size() -> usize {
+    20
+}
+
 impl Default for ColorSchemeCommand {
     fn default() -> Self {
         ColorSchemeCommand {
@@ -41,6 +91,8 @@ impl Default for ColorSchemeCommand {
             duration: default_colorscheme_command_duration(),
             default: default_colorscheme_name(),
             background: default_background(),
+            list: default_colorscheme_list_command_name(),
+            page_size: default_colorscheme_page_size(),
         }
     }
 }
@@ -49,24 +101,229 @@ impl Default for ColorSchemeCommand {
 #[serde(deny_unknown_fields)]
 pub struct VimMotionsHellCommand {
     #[serde(default = "default_vimhell_command_name")]
-    pub name: String,
+    pub name: Triggers,
     #[serde(default = "default_vimhell_command_duration")]
     pub duration: u32,
+    /// Keys and the motions they are mapped to in normal, visual and operator-pending modes
+    #[serde(default = "default_vimhell_mappings")]
+    pub mappings: HashMap<String, String>,
+    /// Also swap the arrow keys in insert mode
+    #[serde(default)]
+    pub insert_arrows: bool,
 }
 
-fn default_vimhell_command_name() -> String {
-    String::from("!vimhell")
+fn default_vimhell_command_name() -> Triggers {
+    Triggers::from("!vimhell")
 }
 
 fn default_vimhell_command_duration() -> u32 {
     60
 }
 
+fn default_vimhell_mappings() -> HashMap<String, String> {
+    [
+        ("l", "h"),
+        ("k", "j"),
+        ("j", "k"),
+        ("h", "l"),
+        ("w", "b"),
+        ("b", "w"),
+        ("e", "ge"),
+        ("ge", "e"),
+    ]
+    .into_iter()
+    .map(|(lhs, rhs)| (String::from(lhs), String::from(rhs)))
+    .collect()
+}
+
 impl Default for VimMotionsHellCommand {
     fn default() -> Self {
         VimMotionsHellCommand {
             name: default_vimhell_command_name(),
             duration: default_vimhell_command_duration(),
+            mappings: default_vimhell_mappings(),
+            insert_arrows: false,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct KeyboardShuffleCommand {
+    #[serde(default = "default_shuffle_command_name")]
+    pub name: Triggers,
+    #[serde(default = "default_shuffle_command_duration")]
+    pub duration: u32,
+    /// Groups of keys, every group is shuffled on its own
+    #[serde(default = "default_shuffle_keys")]
+    pub keys: Vec<Vec<String>>,
+}
+
+fn default_shuffle_command_name() -> Triggers {
+    Triggers::from("!shuffle")
+}
+
+fn default_shuffle_command_duration() -> u32 {
+    60
+}
+
+fn default_shuffle_keys() -> Vec<Vec<String>> {
+    [
+        &["h", "j", "k", "l"][..],
+        &["w", "b", "e"],
+        &["x", "d", "y"],
+    ]
+    .iter()
+    .map(|group| group.iter().map(|x| String::from(*x)).collect())
+    .collect()
+}
+
+impl Default for KeyboardShuffleCommand {
+    fn default() -> Self {
+        KeyboardShuffleCommand {
+            name: default_shuffle_command_name(),
+            duration: default_shuffle_command_duration(),
+            keys: default_shuffle_keys(),
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct InputLagCommand {
+    #[serde(default = "default_lag_command_name")]
+    pub name: Triggers,
+    #[serde(default = "default_lag_command_duration")]
+    pub duration: u32,
+    /// Milliseconds every key is held back
+    #[serde(default = "default_lag_delay")]
+    pub delay: u32,
+    /// Up to this many milliseconds are randomly added to the delay
+    #[serde(default = "default_lag_jitter")]
+    pub jitter: u32,
+}
+
+fn default_lag_command_name() -> Triggers {
+    Triggers::from("!lag")
+}
+
+fn default_lag_command_duration() -> u32 {
+    60
+}
+
+fn default_lag_delay() -> u32 {
+    200
+}
+
+fn default_lag_jitter() -> u32 {
+    300
+}
+
+impl Default for InputLagCommand {
+    fn default() -> Self {
+        InputLagCommand {
+            name: default_lag_command_name(),
+            duration: default_lag_command_duration(),
+            delay: default_lag_delay(),
+            jitter: default_lag_jitter(),
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct TypoCommand {
+    #[serde(default = "default_typo_command_name")]
+    pub name: Triggers,
+    #[serde(default = "default_typo_command_duration")]
+    pub duration: u32,
+    /// Chance of a typed character to be swapped, duplicated or dropped, from 0 to 1
+    #[serde(default = "default_typo_rate")]
+    pub rate: f64,
+}
+
+fn default_typo_command_name() -> Triggers {
+    Triggers::from("!typo")
+}
+
+fn default_typo_command_duration() -> u32 {
+    60
+}
+
+fn default_typo_rate() -> f64 {
+    0.1
+}
+
+impl Default for TypoCommand {
+    fn default() -> Self {
+        TypoCommand {
+            name: default_typo_command_name(),
+            duration: default_typo_command_duration(),
+            rate: default_typo_rate(),
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct BanKeyCommand {
+    #[serde(default = "default_ban_command_name")]
+    pub name: Triggers,
+    #[serde(default = "default_ban_command_duration")]
+    pub duration: u32,
+    /// Keys viewers are allowed to ban, `:` and `<Esc>` never are
+    #[serde(default = "default_ban_keys")]
+    pub keys: Vec<String>,
+}
+
+fn default_ban_command_name() -> Triggers {
+    Triggers::from("!ban")
+}
+
+fn default_ban_command_duration() -> u32 {
+    60
+}
+
+fn default_ban_keys() -> Vec<String> {
+    [
+        "h", "j", "k", "l", "w", "b", "e", "x", "d", "y", "p", "u", "o", "a",
+    ]
+    .map(String::from)
+    .to_vec()
+}
+
+impl Default for BanKeyCommand {
+    fn default() -> Self {
+        BanKeyCommand {
+            name: default_ban_command_name(),
+            duration: default_ban_command_duration(),
+            keys: default_ban_keys(),
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct FlipCommand {
+    #[serde(default = "default_flip_command_name")]
+    pub name: Triggers,
+    #[serde(default = "default_flip_command_duration")]
+    pub duration: u32,
+}
+
+fn default_flip_command_name() -> Triggers {
+    Triggers::from("!flip")
+}
+
+fn default_flip_command_duration() -> u32 {
+    60
+}
+
+impl Default for FlipCommand {
+    fn default() -> Self {
+        FlipCommand {
+            name: default_flip_command_name(),
+            duration: default_flip_command_duration(),
         }
     }
 }
@@ -75,25 +332,385 @@ impl Default for VimMotionsHellCommand {
 #[serde(deny_unknown_fields)]
 pub struct Commands {
     #[serde(default = "default_message_command_name")]
-    pub message: String,
+    pub message: Triggers,
 
     #[serde(default)]
     pub colorscheme: ColorSchemeCommand,
 
     #[serde(default)]
     pub hell: VimMotionsHellCommand,
+
+    #[serde(default)]
+    pub shuffle: KeyboardShuffleCommand,
+
+    #[serde(default)]
+    pub lag: InputLagCommand,
+
+    #[serde(default)]
+    pub typo: TypoCommand,
+
+    #[serde(default)]
+    pub ban: BanKeyCommand,
+
+    #[serde(default)]
+    pub flip: FlipCommand,
+
+    #[serde(default = "default_help_command_name")]
+    pub help: Triggers,
+
+    #[serde(default = "default_vote_command_name")]
+    pub vote: Triggers,
+
+    /// Match command names regardless of the case
+    #[serde(default)]
+    pub ignore_case: bool,
+}
+
+fn default_message_command_name() -> Triggers {
+    Triggers::from("!msg")
+}
+
+fn default_help_command_name() -> Triggers {
+    Triggers::from("!chaos")
 }
 
-fn default_message_command_name() -> String {
-    String::from("!msg")
+fn default_vote_command_name() -> Triggers {
+    Triggers::from("!vote")
 }
 
 impl Default for Commands {
     fn default() -> Self {
         Commands {
             message: default_message_command_name(),
+            help: default_help_command_name(),
+            vote: default_vote_command_name(),
+            ignore_case: false,
             colorscheme: ColorSchemeCommand::default(),
             hell: VimMotionsHellCommand::default(),
+            shuffle: KeyboardShuffleCommand::default(),
+            lag: InputLagCommand::default(),
+            typo: TypoCommand::default(),
+            ban: BanKeyCommand::default(),
+            flip: FlipCommand::default(),
+        }
+    }
+}
+
+/// Chaos is decided by polls instead of direct commands
+#[derive(Deserialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct VotingConfig {
+    /// Seconds between polls
+    #[serde(default = "default_voting_interval")]
+    pub interval: u32,
+    /// Seconds a poll stays open
+    #[serde(default = "default_voting_duration")]
+    pub duration: u32,
+    #[serde(default = "default_voting_options")]
+    pub options: usize,
+}
+
+fn default_voting_interval() -> u32 {
+    60 * 5
+}
+
+fn default_voting_duration() -> u32 {
+    60
+}
+
+fn default_voting_options() -> usize {
+    3
+}
+
+/// Cooldowns of a command in seconds, zero disables the cooldown
+#[derive(Default, Deserialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct CooldownConfig {
+    #[serde(default)]
+    pub global: u32,
+    #[serde(default)]
+    pub user: u32,
+}
+
+/// What happens when a mode is triggered while another one of its type is active
+#[derive(Default, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Policy {
+    /// The new mode replaces the active one
+    #[default]
+    Replace,
+    /// The active mode gets the duration of the new one
+    Extend,
+    /// The new mode starts once the active one is over
+    Queue,
+    /// The new mode is dropped
+    Ignore,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct ModeConfig {
+    #[serde(default)]
+    pub policy: Policy,
+    /// Most seconds an extended mode can have left, unlimited when unset
+    #[serde(default)]
+    pub cap: Option<u32>,
+    /// Most modes waiting behind the active one
+    #[serde(default = "default_mode_queue")]
+    pub queue: usize,
+}
+
+fn default_mode_queue() -> usize {
+    3
+}
+
+impl Default for ModeConfig {
+    fn default() -> Self {
+        ModeConfig {
+            policy: Policy::default(),
+            cap: None,
+            queue: default_mode_queue(),
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct PauseConfig {
+    /// Revert the active modes while paused and apply them again on resume
+    #[serde(default = "default_pause_stop_modes")]
+    pub stop_modes: bool,
+}
+
+fn default_pause_stop_modes() -> bool {
+    true
+}
+
+impl Default for PauseConfig {
+    fn default() -> Self {
+        PauseConfig {
+            stop_modes: default_pause_stop_modes(),
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct PanicConfig {
+    /// Seconds commands are refused after a panic
+    #[serde(default = "default_panic_lockout")]
+    pub lockout: u32,
+}
+
+fn default_panic_lockout() -> u32 {
+    60 * 5
+}
+
+impl Default for PanicConfig {
+    fn default() -> Self {
+        PanicConfig {
+            lockout: default_panic_lockout(),
+        }
+    }
+}
+
+/// Bounds of the duration viewers can ask for, in seconds
+#[derive(Deserialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct DurationConfig {
+    #[serde(default = "default_duration_min")]
+    pub min: u32,
+    /// Hard limit, scaled durations included, defaults to the duration of the command
+    #[serde(default)]
+    pub max: Option<u32>,
+    /// Multiplier by role, the closest role at or below the viewer's applies
+    #[serde(default)]
+    pub scale: HashMap<Role, f32>,
+}
+
+fn default_duration_min() -> u32 {
+    10
+}
+
+impl DurationConfig {
+    /// Duration of the mode, `requested` by the viewer or the `default` of the command
+    pub fn resolve(&self, default: u32, requested: Option<u32>, role: Role) -> u32 {
+        let scale = self
+            .scale
+            .iter()
+            .filter(|(x, _)| **x <= role)
+            .max_by_key(|(x, _)| **x)
+            .map_or(1.0, |(_, scale)| *scale);
+
+        let seconds = (requested.unwrap_or(default) as f32 * scale).round() as u32;
+        let min = self.min.max(1);
+        let max = self.max.unwrap_or(default).max(min);
+
+        seconds.clamp(min, max)
+    }
+}
+
+/// Chat role, ordered from the least to the most privileged
+#[derive(Default, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    #[default]
+    Everyone,
+    Subscriber,
+    Vip,
+    Moderator,
+    Broadcaster,
+}
+
+impl Role {
+    /// Highest role granted by the Twitch badges
+    pub fn from_badges(badges: &[String]) -> Self {
+        badges
+            .iter()
+            .map(|badge| match badge.as_str() {
+                "broadcaster" => Role::Broadcaster,
+                "moderator" => Role::Moderator,
+                "vip" => Role::Vip,
+                "subscriber" | "founder" => Role::Subscriber,
+                _ => Role::Everyone,
+            })
+            .max()
+            .unwrap_or_default()
+    }
+}
+
+impl Display for Role {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let string = match self {
+            Role::Everyone => "everyone",
+            Role::Subscriber => "subscriber",
+            Role::Vip => "VIP",
+            Role::Moderator => "moderator",
+            Role::Broadcaster => "broadcaster",
+        };
+
+        write!(f, "{string}")
+    }
+}
+
+/// Who is allowed to use a command, the lists take precedence over the role
+#[derive(Default, Deserialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct PermissionConfig {
+    #[serde(default)]
+    pub role: Role,
+    #[serde(default)]
+    pub allow: Vec<String>,
+    #[serde(default)]
+    pub deny: Vec<String>,
+}
+
+impl PermissionConfig {
+    pub fn is_allowed(&self, user: &str, role: Role) -> bool {
+        let contains = |users: &[String]| users.iter().any(|x| x.eq_ignore_ascii_case(user));
+
+        if contains(&self.deny) {
+            return false;
+        }
+
+        contains(&self.allow) || role >= self.role
+    }
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct ChannelOptions {
+    pub name: String,
+    /// Keys of the enabled commands (`message`, `colorscheme`, `hell`...), all when unset
+    #[serde(default)]
+    pub commands: Option<Vec<String>>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(untagged)]
+pub enum ChannelConfig {
+    Name(String),
+    Options(ChannelOptions),
+}
+
+impl ChannelConfig {
+    pub fn name(&self) -> &str {
+        match self {
+            ChannelConfig::Name(name) => name,
+            ChannelConfig::Options(options) => &options.name,
+        }
+    }
+
+    pub fn allows(&self, command: &str) -> bool {
+        match self {
+            ChannelConfig::Name(_) => true,
+            ChannelConfig::Options(options) => options
+                .commands
+                .as_ref()
+                .is_none_or(|commands| commands.iter().any(|x| x == command)),
+        }
+    }
+}
+
+/// Twitch bot account, the token is never stored in the config itself
+#[derive(Deserialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct AuthConfig {
+    pub username: String,
+    #[serde(default)]
+    pub token_env: Option<String>,
+    #[serde(default)]
+    pub token_file: Option<String>,
+}
+
+impl AuthConfig {
+    pub fn token(&self) -> Result<String, Error> {
+        let token = if let Some(name) = &self.token_env {
+            std::env::var(name).map_err(|err| Error::Token {
+                origin: format!("${name}"),
+                why: err.to_string(),
+            })?
+        } else if let Some(path) = &self.token_file {
+            std::fs::read_to_string(path).map_err(|err| Error::Token {
+                origin: path.clone(),
+                why: err.to_string(),
+            })?
+        } else {
+            return Err(Error::Token {
+                origin: String::from("config"),
+                why: String::from("`token_env` or `token_file` is required"),
+            });
+        };
+
+        // Tokens are often copied together with the IRC prefix
+        Ok(token.trim().trim_start_matches("oauth:").to_owned())
+    }
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct IrcConfig {
+    pub host: String,
+    #[serde(default)]
+    pub port: Option<u16>,
+    #[serde(default)]
+    pub tls: bool,
+    #[serde(default = "default_irc_nick")]
+    pub nick: String,
+    #[serde(default)]
+    pub channels: Vec<String>,
+}
+
+fn default_irc_nick() -> String {
+    String::from("nvim-chaos")
+}
+
+impl IrcConfig {
+    pub fn port(&self) -> u16 {
+        match (self.port, self.tls) {
+            (Some(port), _) => port,
+            (None, true) => 6697,
+            (None, false) => 6667,
         }
     }
 }
@@ -104,8 +721,97 @@ pub struct Config {
     #[serde(default)]
     pub channel: Option<String>,
 
+    #[serde(default)]
+    pub channels: Vec<ChannelConfig>,
+
+    #[serde(default)]
+    pub auth: Option<AuthConfig>,
+
+    /// Path of a Unix socket accepting line-delimited JSON chat events
+    #[serde(default)]
+    pub socket: Option<String>,
+
+    #[serde(default)]
+    pub irc: Option<IrcConfig>,
+
     #[serde(default)]
     pub commands: Commands,
+
+    /// Cooldowns by command key
+    #[serde(default)]
+    pub cooldowns: HashMap<String, CooldownConfig>,
+
+    /// Duration bounds by command key
+    #[serde(default)]
+    pub durations: HashMap<String, DurationConfig>,
+
+    /// Mode options by command key
+    #[serde(default)]
+    pub modes: HashMap<String, ModeConfig>,
+
+    /// Permissions by command key, commands without one are open to everyone
+    #[serde(default)]
+    pub permissions: HashMap<String, PermissionConfig>,
+
+    #[serde(default)]
+    pub voting: Option<VotingConfig>,
+
+    #[serde(default)]
+    pub pause: PauseConfig,
+
+    #[serde(default)]
+    pub panic: PanicConfig,
+}
+
+impl Config {
+    /// Twitch channels to join, `channel` followed by `channels`
+    pub fn twitch_channels(&self) -> Vec<String> {
+        self.channel
+            .iter()
+            .map(String::as_str)
+            .chain(self.channels.iter().map(ChannelConfig::name))
+            .map(String::from)
+            .collect()
+    }
+
+    /// Checks the options keyed by command against the keys of the known commands
+    pub fn validate(&self, keys: &[&str]) -> Result<(), Error> {
+        let tables = [
+            ("cooldowns", self.cooldowns.keys().collect::<Vec<_>>()),
+            ("durations", self.durations.keys().collect()),
+            ("modes", self.modes.keys().collect()),
+            ("permissions", self.permissions.keys().collect()),
+        ];
+
+        let channels = self.channels.iter().filter_map(|x| match x {
+            ChannelConfig::Name(_) => None,
+            ChannelConfig::Options(options) => options.commands.as_ref(),
+        });
+
+        let used = tables
+            .into_iter()
+            .flat_map(|(table, keys)| keys.into_iter().map(move |x| (table, x)))
+            .chain(channels.flatten().map(|x| ("channels.commands", x)));
+
+        for (table, key) in used {
+            if !keys.contains(&key.as_str()) {
+                return Err(Error::BadConfig {
+                    prefix: String::new(),
+                    option: format!("{table}.{key}"),
+                    why: format!("unknown command `{key}`"),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn is_command_enabled(&self, channel: &str, command: &str) -> bool {
+        self.channels
+            .iter()
+            .filter(|x| x.name().eq_ignore_ascii_case(channel))
+            .all(|x| x.allows(command))
+    }
 }
 
 impl TryFrom<Object> for Config {
@@ -120,3 +826,92 @@ impl TryFrom<Object> for Config {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEYS: &[&str] = &["hell", "shuffle"];
+
+    fn durations(min: u32, max: Option<u32>) -> DurationConfig {
+        DurationConfig {
+            min,
+            max,
+            scale: HashMap::from([(Role::Subscriber, 2.0), (Role::Moderator, 4.0)]),
+        }
+    }
+
+    #[test]
+    fn scales_by_the_closest_role() {
+        let durations = durations(10, Some(1000));
+
+        assert_eq!(durations.resolve(60, Some(30), Role::Everyone), 30);
+        assert_eq!(durations.resolve(60, Some(30), Role::Vip), 60);
+        assert_eq!(durations.resolve(60, Some(30), Role::Broadcaster), 120);
+        assert_eq!(durations.resolve(60, None, Role::Subscriber), 120);
+    }
+
+    #[test]
+    fn caps_at_the_command_duration_by_default() {
+        let durations = durations(10, None);
+
+        assert_eq!(durations.resolve(60, Some(300), Role::Everyone), 60);
+        assert_eq!(durations.resolve(60, Some(40), Role::Moderator), 60);
+        assert_eq!(durations.resolve(60, Some(5), Role::Everyone), 10);
+    }
+
+    #[test]
+    fn min_wins_over_a_smaller_max() {
+        let durations = durations(90, Some(30));
+
+        assert_eq!(durations.resolve(60, Some(10), Role::Everyone), 90);
+        assert_eq!(durations.resolve(60, Some(600), Role::Moderator), 90);
+    }
+
+    #[test]
+    fn never_resolves_to_zero() {
+        let durations = DurationConfig {
+            min: 0,
+            max: None,
+            scale: HashMap::from([(Role::Everyone, 0.0)]),
+        };
+
+        assert_eq!(durations.resolve(60, Some(30), Role::Everyone), 1);
+    }
+
+    #[test]
+    fn accepts_known_command_keys() {
+        let mut config = Config::default();
+        config
+            .cooldowns
+            .insert(String::from("hell"), CooldownConfig::default());
+
+        assert!(config.validate(KEYS).is_ok());
+    }
+
+    #[test]
+    fn rejects_unknown_command_keys() {
+        let mut config = Config::default();
+        config
+            .modes
+            .insert(String::from("hel"), ModeConfig::default());
+
+        let error = config.validate(KEYS).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "error parsing `modes.hel`: unknown command `hel`"
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_channel_commands() {
+        let mut config = Config::default();
+        config.channels.push(ChannelConfig::Options(ChannelOptions {
+            name: String::from("streamer"),
+            commands: Some(vec![String::from("shufle")]),
+        }));
+
+        assert!(config.validate(KEYS).is_err());
+    }
+}
@@ -7,16 +7,19 @@ pub enum Error {
     #[error("error parsing `{prefix}{option}`: {why}")]
     BadConfig {
         prefix: String,
-        option: serde_path_to_error::Path,
+        option: String,
         why: String,
     },
+
+    #[error("can't read OAuth token from {origin}: {why}")]
+    Token { origin: String, why: String },
 }
 
 impl From<serde_path_to_error::Error<DeserializeError>> for Error {
     fn from(err: serde_path_to_error::Error<DeserializeError>) -> Self {
         Self::BadConfig {
             prefix: String::new(),
-            option: err.path().clone(),
+            option: err.path().to_string(),
             why: err.into_inner().to_string(),
         }
     }
@@ -1,4 +1,7 @@
 mod config;
 mod error;
 
-pub(crate) use config::Config;
+pub(crate) use config::{
+    Config, CooldownConfig, IrcConfig, ModeConfig, Policy, Role, VotingConfig,
+};
+pub(crate) use error::Error;
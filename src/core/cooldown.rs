@@ -0,0 +1,125 @@
+use super::config::CooldownConfig;
+
+#[derive(Clone)]
+pub struct Cooldown {
+    /// Key of the command in the config
+    pub command: String,
+    /// Name shown in the status window, e.g. `!vimhell`
+    pub name: String,
+    /// Author the cooldown applies to, `None` for a global cooldown
+    pub user: Option<String>,
+    pub seconds: u32,
+}
+
+#[derive(Clone, Default)]
+pub struct Cooldowns {
+    pub entries: Vec<Cooldown>,
+}
+
+impl Cooldowns {
+    /// Seconds left until the user is allowed to use the command again
+    pub fn remaining(&self, command: &str, user: &str) -> Option<u32> {
+        self.entries
+            .iter()
+            .filter(|x| x.command == command)
+            .filter(|x| x.user.as_deref().is_none_or(|x| x == user))
+            .map(|x| x.seconds)
+            .max()
+    }
+
+    pub fn start(&mut self, command: &str, name: &str, user: &str, config: &CooldownConfig) {
+        let cooldowns = [(None, config.global), (Some(user.to_owned()), config.user)];
+
+        for (user, seconds) in cooldowns {
+            if seconds == 0 {
+                continue;
+            }
+
+            self.entries
+                .retain(|x| !(x.command == command && x.user == user));
+
+            self.entries.push(Cooldown {
+                command: command.to_owned(),
+                name: name.to_owned(),
+                user,
+                seconds,
+            });
+        }
+    }
+
+    pub fn tick(&mut self) {
+        for cooldown in &mut self.entries {
+            cooldown.seconds = cooldown.seconds.saturating_sub(1);
+        }
+
+        self.entries.retain(|x| x.seconds != 0);
+    }
+
+    /// Global cooldowns, per-user ones would flood the status window on a busy chat
+    pub fn global(&self) -> impl Iterator<Item = &Cooldown> {
+        self.entries.iter().filter(|x| x.user.is_none())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn started(global: u32, user: u32) -> Cooldowns {
+        let mut cooldowns = Cooldowns::default();
+        cooldowns.start(
+            "hell",
+            "!vimhell",
+            "alice",
+            &CooldownConfig { global, user },
+        );
+        cooldowns
+    }
+
+    #[test]
+    fn applies_the_longest_cooldown() {
+        let cooldowns = started(10, 30);
+
+        assert_eq!(cooldowns.remaining("hell", "alice"), Some(30));
+        assert_eq!(cooldowns.remaining("hell", "bob"), Some(10));
+        assert_eq!(cooldowns.remaining("shuffle", "alice"), None);
+    }
+
+    #[test]
+    fn skips_zero_cooldowns() {
+        let cooldowns = started(0, 5);
+
+        assert_eq!(cooldowns.remaining("hell", "bob"), None);
+        assert_eq!(cooldowns.global().count(), 0);
+    }
+
+    #[test]
+    fn restarting_replaces_the_cooldown() {
+        let mut cooldowns = started(10, 0);
+        cooldowns.tick();
+        cooldowns.start(
+            "hell",
+            "!vimhell",
+            "bob",
+            &CooldownConfig {
+                global: 10,
+                user: 0,
+            },
+        );
+
+        assert_eq!(cooldowns.entries.len(), 1);
+        assert_eq!(cooldowns.remaining("hell", "alice"), Some(10));
+    }
+
+    #[test]
+    fn expires_after_ticking() {
+        let mut cooldowns = started(2, 1);
+
+        cooldowns.tick();
+        assert_eq!(cooldowns.remaining("hell", "alice"), Some(1));
+
+        cooldowns.tick();
+        assert_eq!(cooldowns.remaining("hell", "alice"), None);
+        assert!(cooldowns.entries.is_empty());
+    }
+}
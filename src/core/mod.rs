@@ -1,3 +1,5 @@
 pub mod chaos_mode;
 pub mod config;
+pub mod cooldown;
 pub mod plugin;
+pub mod voting;
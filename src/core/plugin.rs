@@ -1,9 +1,13 @@
 use std::io::Cursor;
 use std::sync::OnceLock;
-use std::{cell::RefCell, rc::Rc, str::FromStr, thread, time::Duration};
+use std::{cell::RefCell, rc::Rc, thread, time::Duration};
 
 use nvim_oxi::{
-    api::{self, opts::EchoOpts},
+    api::{
+        self,
+        opts::{CreateCommandOpts, EchoOpts},
+        types::{CommandArgs, LogLevel},
+    },
     libuv::{AsyncHandle, TimerHandle},
     schedule, Dictionary, Function, Object,
 };
@@ -12,19 +16,29 @@ use rodio::Decoder;
 use tokio::sync::mpsc;
 
 use crate::{
-    commands::{Background, ColorSchemeCommand, Mode, ModeType, VimMotionsHellCommand},
-    twitch::{self},
+    chat::{self, irc::IrcSource, twitch::TwitchSource, ChatSource},
+    commands::{
+        installed_color_schemes,
+        registry::{Action, CommandDef, Registry},
+        Mode, ModeCommand, ModeSpec, ModeType,
+    },
 };
 
 use super::{
-    chaos_mode::{self},
-    config::Config,
+    chaos_mode::{self, Activation},
+    config::{Config, VotingConfig},
+    voting::{self, Poll},
 };
 
-use crate::error::Result;
+use crate::error::{Error, Result};
+
+#[cfg(unix)]
+use crate::chat::socket::SocketSource;
 
 pub static CONFIG: OnceLock<Config> = OnceLock::new();
 
+pub static REGISTRY: OnceLock<Registry> = OnceLock::new();
+
 #[derive(Clone, Default)]
 pub struct State {
     pub chaos_mode: chaos_mode::State,
@@ -39,91 +53,316 @@ impl Plugin {
     pub fn init(&mut self) -> Result<()> {
         let config = CONFIG.get().unwrap();
 
-        let (sender, mut receiver) = mpsc::unbounded_channel::<twitch::CommandPayload>();
+        self.create_user_commands()?;
+
+        let (sender, mut receiver) = mpsc::unbounded_channel::<chat::CommandPayload>();
 
         let plugin = self.clone();
 
+        // Several sources may send before the handle fires, libuv coalesces those into one call
         let handle = AsyncHandle::new(move || {
-            let payload = receiver.blocking_recv();
-
-            if let Some(payload) = payload {
+            while let Ok(payload) = receiver.try_recv() {
                 plugin.handle_payload(payload);
-            } else {
-                schedule(move |()| {
-                    Plugin::err("Payload receiving error");
-                });
             }
         })?;
 
-        let config = config.clone();
+        let mut has_source = false;
 
-        if config.channel.is_some() {
-            thread::spawn(move || {
-                twitch::init(handle, sender, config).unwrap_or_else(|error| {
-                    schedule(move |()| {
-                        Plugin::err(error.to_string().as_str());
-                    });
-                });
-            });
+        let channels = config.twitch_channels();
 
-            self.start_timer()?;
+        if !channels.is_empty() {
+            let mut source = TwitchSource::new(channels);
+
+            if let Some(auth) = &config.auth {
+                source = source.with_login(auth.username.clone(), auth.token()?);
+            }
+
+            Plugin::spawn_source(source, handle.clone(), sender.clone(), config.clone());
+            has_source = true;
+        }
+
+        if let Some(irc) = config.irc.clone() {
+            let source = IrcSource::new(irc);
+            Plugin::spawn_source(source, handle.clone(), sender.clone(), config.clone());
+            has_source = true;
+        }
+
+        #[cfg(unix)]
+        if let Some(path) = config.socket.clone() {
+            let source = SocketSource::new(path);
+            Plugin::spawn_source(source, handle.clone(), sender.clone(), config.clone());
+            has_source = true;
+        }
 
-            let mut state = self.state.borrow_mut();
+        #[cfg(not(unix))]
+        if config.socket.is_some() {
+            Plugin::err("socket is only supported on unix");
+        }
+
+        self.state.borrow_mut().chaos_mode.init()?;
 
-            state.chaos_mode.init()?;
+        if has_source {
+            self.start_timer()?;
         }
 
         Ok(())
     }
 
-    fn handle_payload(&self, payload: twitch::CommandPayload) {
+    fn spawn_source<S>(
+        source: S,
+        handle: AsyncHandle,
+        sender: mpsc::UnboundedSender<chat::CommandPayload>,
+        config: Config,
+    ) where
+        S: ChatSource + Send + 'static,
+    {
+        let registry = REGISTRY.get().unwrap();
+
+        thread::spawn(move || {
+            chat::run(source, handle, sender, config, registry).unwrap_or_else(|error| {
+                schedule(move |()| {
+                    Plugin::err(error.to_string().as_str());
+                });
+            });
+        });
+    }
+
+    fn handle_payload(&self, payload: chat::CommandPayload) {
         let mut plugin = self.clone();
 
         schedule(move |()| {
-            if let Err(error) = plugin.parse_command(payload.command) {
+            if let Err(error) = plugin.parse_command(payload) {
                 Plugin::err(error.to_string().as_str());
             }
         });
     }
 
-    fn parse_command(&mut self, command: twitch::Command) -> Result<()> {
+    fn parse_command(&mut self, payload: chat::CommandPayload) -> Result<()> {
         let config = CONFIG.get().unwrap();
+        let registry = REGISTRY.get().unwrap();
 
-        match command {
-            twitch::Command::Message(author, text) => {
-                Plugin::show_msg(author.as_str(), text.as_str())?;
+        let Some(command) = registry.get(payload.command.key) else {
+            return Ok(());
+        };
+
+        let key = command.key;
+        let name = command.name();
+
+        let lockout = self.state.borrow().chaos_mode.lockout;
+
+        if lockout > 0 {
+            payload.reply(&format!("chaos is locked for {lockout}s"));
+            return Ok(());
+        }
+
+        if let Some(permission) = config.permissions.get(key) {
+            if !permission.is_allowed(&payload.author, payload.role) {
+                let reply = if permission.role > payload.role {
+                    format!("{name} is for {} and above", permission.role)
+                } else {
+                    format!("you are not allowed to use {name}")
+                };
+
+                payload.reply(&reply);
+                return Ok(());
             }
-            twitch::Command::ColorScheme(colorscheme, background) => {
-                let background = Background::from_str(&background).unwrap();
-                let mode: Mode = ColorSchemeCommand {
-                    colorscheme,
-                    background,
+        }
+
+        if config.voting.is_some() && command.is_voted() {
+            payload.reply(&format!(
+                "chaos is decided by vote, use {}",
+                config.commands.vote.first()
+            ));
+            return Ok(());
+        }
+
+        if command.is_mode() && self.state.borrow().chaos_mode.is_paused {
+            payload.reply("chaos is paused");
+            return Ok(());
+        }
+
+        let remaining = {
+            let state = self.state.borrow();
+            state.chaos_mode.cooldowns.remaining(key, &payload.author)
+        };
+
+        if let Some(seconds) = remaining {
+            payload.reply(&format!("{name} is on cooldown for {seconds}s"));
+            return Ok(());
+        }
+
+        match self.run_command(command, &payload) {
+            Ok(true) => {
+                if let Some(cooldown) = config.cooldowns.get(key) {
+                    let mut state = self.state.borrow_mut();
+                    state
+                        .chaos_mode
+                        .start_cooldown(key, name, &payload.author, cooldown)?;
                 }
-                .into();
-                self.set_mode(
-                    mode,
-                    ModeType::ColorSchemeType,
-                    config.commands.colorscheme.duration,
-                )?;
             }
-            twitch::Command::VimMotionsHell => {
-                let mode: Mode = VimMotionsHellCommand {}.into();
-                self.set_mode(
-                    mode,
-                    ModeType::VimMotionsHellType,
-                    config.commands.hell.duration,
-                )?;
+            Ok(false) => {}
+            Err(Error::Rejected(reason)) => {
+                let message = format!("{}: {reason}", payload.author);
+                Plugin::notify(name, &message, LogLevel::Warn)?;
+                payload.reply(&reason);
             }
+            Err(error) => return Err(error),
         }
 
         Ok(())
     }
 
+    /// Runs the command, returns `false` if it was rejected
+    fn run_command(
+        &mut self,
+        command: &CommandDef,
+        payload: &chat::CommandPayload,
+    ) -> Result<bool> {
+        let config = CONFIG.get().unwrap();
+        let args = payload
+            .command
+            .args
+            .as_ref()
+            .map_err(|error| Error::Rejected(format!("{error}, usage: {}", command.usage())))?;
+
+        let is_used = match command.action {
+            Action::Mode(build) => {
+                let mut spec = build(args, config)?;
+
+                let requested = args.duration("duration");
+
+                spec.seconds = match config.durations.get(command.key) {
+                    Some(durations) => durations.resolve(spec.seconds, requested, payload.role),
+                    // Without bounds viewers can only shorten the mode
+                    None => requested.map_or(spec.seconds, |x| x.min(spec.seconds)),
+                };
+
+                self.activate(payload, spec)?
+            }
+            Action::Message => {
+                let title = if payload.channel.is_empty() {
+                    payload.author.clone()
+                } else {
+                    format!("{} [{}]", payload.author, payload.channel)
+                };
+                let text = args.text("text").unwrap_or_default();
+
+                Plugin::show_msg(title.as_str(), text)?;
+                true
+            }
+            Action::ColorSchemes => {
+                let page = args.integer("page").unwrap_or(1);
+                let page = usize::try_from(page).unwrap_or(1);
+
+                let schemes = installed_color_schemes()?;
+                let page_size = config.commands.colorscheme.page_size.max(1);
+                let pages = schemes.len().div_ceil(page_size).max(1);
+                let page = page.clamp(1, pages);
+
+                let names = schemes
+                    .iter()
+                    .skip((page - 1) * page_size)
+                    .take(page_size)
+                    .map(String::as_str)
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let title = format!("Color schemes {page}/{pages}");
+
+                if payload.can_reply() {
+                    payload.reply(&format!("{title}: {names}"));
+                } else {
+                    Plugin::notify(&title, &wrap_text(&names, 40), LogLevel::Info)?;
+                }
+
+                true
+            }
+            Action::Help => {
+                let usages: Vec<String> = REGISTRY
+                    .get()
+                    .unwrap()
+                    .iter()
+                    .filter(|x| config.is_command_enabled(&payload.channel, x.key))
+                    .filter(|x| config.voting.is_none() || !x.is_voted())
+                    .map(CommandDef::usage)
+                    .collect();
+
+                if payload.can_reply() {
+                    payload.reply(&usages.join(" | "));
+                } else {
+                    let usages = usages.join("\n");
+                    Plugin::notify("Chaos Neovim commands", &usages, LogLevel::Info)?;
+                }
+
+                true
+            }
+            Action::Vote => {
+                let mut state = self.state.borrow_mut();
+                let chaos_mode = &mut state.chaos_mode;
+
+                let Some(poll) = &mut chaos_mode.voting.poll else {
+                    return Err(Error::Rejected(String::from("no poll is open")));
+                };
+
+                let option = args.integer("option").unwrap_or_default();
+                let option = usize::try_from(option).unwrap_or_default();
+
+                if !poll.vote(&payload.author, option) {
+                    let options = poll.options.len();
+                    return Err(Error::Rejected(format!(
+                        "pick an option from 1 to {options}"
+                    )));
+                }
+
+                chaos_mode.update()?;
+
+                true
+            }
+        };
+
+        Ok(is_used)
+    }
+
+    /// Sets the mode and confirms it in chat, returns `false` if the mode wasn't used
+    fn activate(&mut self, payload: &chat::CommandPayload, spec: ModeSpec) -> Result<bool> {
+        let name = spec.mode.name();
+        let seconds = spec.seconds;
+        let activation = self.set_mode(spec.mode, spec.mode_type, seconds)?;
+
+        let reply = match &activation {
+            Activation::Started => format!("{name} activated for {seconds}s"),
+            Activation::Extended { active, added: 0 } => {
+                format!("{active} is already at its maximum time")
+            }
+            Activation::Extended { active, added } if active == name => {
+                format!("{active} extended by {added}s")
+            }
+            Activation::Extended { active, added } => {
+                format!("{active} is active and was extended by {added}s instead of {name}")
+            }
+            Activation::Queued => format!("{name} queued for {seconds}s"),
+            Activation::QueueFull => String::from("the queue is full, try again later"),
+            Activation::Ignored => String::from("a mode of this kind is already active"),
+            Activation::Invalid => format!("{name} is not available"),
+        };
+
+        payload.reply(&reply);
+
+        Ok(activation.is_used())
+    }
+
     fn parse_config(&mut self, preferences: Object) -> Result<()> {
-        let config = Config::try_from(preferences);
+        let config = Config::try_from(preferences).and_then(|config| {
+            let registry = Registry::new(&config);
+            let keys: Vec<&str> = registry.iter().map(|x| x.key).collect();
+
+            config.validate(&keys)?;
+
+            Ok((config, registry))
+        });
 
         match config {
-            Ok(config) => {
+            Ok((config, registry)) => {
+                let _ = REGISTRY.set(registry);
                 CONFIG.set(config).unwrap();
                 self.init()?;
             }
@@ -151,7 +390,77 @@ impl Plugin {
             });
         });
 
-        Dictionary::from_iter([("setup", setup)])
+        Dictionary::from_iter([
+            ("setup", Object::from(setup)),
+            ("pause", Object::from(self.action(Plugin::pause))),
+            ("resume", Object::from(self.action(Plugin::resume))),
+            ("panic", Object::from(self.action(Plugin::panic))),
+        ])
+    }
+
+    /// Wraps the action into a Lua function reporting its errors
+    fn action(&self, action: fn(&mut Plugin) -> Result<()>) -> Function<(), ()> {
+        let plugin = self.clone();
+
+        Function::from_fn(move |()| {
+            let mut plugin = plugin.clone();
+            action(&mut plugin).unwrap_or_else(|err| {
+                Plugin::err(err.to_string().as_str());
+            });
+        })
+    }
+
+    fn create_user_commands(&self) -> Result<()> {
+        let commands: [(&str, &str, fn(&mut Plugin) -> Result<()>); 3] = [
+            ("ChaosPause", "Pause the chaos timers", Plugin::pause),
+            ("ChaosResume", "Resume the chaos timers", Plugin::resume),
+            ("ChaosPanic", "Revert every chaos mode", Plugin::panic),
+        ];
+
+        for (name, desc, action) in commands {
+            let plugin = self.clone();
+            let opts = CreateCommandOpts::builder().desc(desc).build();
+
+            let command = Function::from_fn(move |_args: CommandArgs| {
+                let mut plugin = plugin.clone();
+                action(&mut plugin).unwrap_or_else(|err| {
+                    Plugin::err(err.to_string().as_str());
+                });
+            });
+
+            api::create_user_command(name, command, &opts)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn pause(&mut self) -> Result<()> {
+        let Some(config) = CONFIG.get() else {
+            return Ok(());
+        };
+
+        let mut state = self.state.borrow_mut();
+        state.chaos_mode.pause(config.pause.stop_modes)
+    }
+
+    pub fn resume(&mut self) -> Result<()> {
+        let Some(config) = CONFIG.get() else {
+            return Ok(());
+        };
+
+        let mut state = self.state.borrow_mut();
+        state.chaos_mode.resume(config.pause.stop_modes)
+    }
+
+    pub fn panic(&mut self) -> Result<()> {
+        let Some(config) = CONFIG.get() else {
+            return Ok(());
+        };
+
+        let mut state = self.state.borrow_mut();
+        state
+            .chaos_mode
+            .panic(config.panic.lockout, config.pause.stop_modes)
     }
 
     pub fn err(str: &str) {
@@ -159,19 +468,67 @@ impl Plugin {
     }
 
     fn update(&mut self) -> Result<()> {
+        let config = CONFIG.get().unwrap();
+        let is_halted = {
+            let state = self.state.borrow();
+            state.chaos_mode.is_paused || state.chaos_mode.lockout > 0
+        };
+
+        // A poll winner failing to start must not stop the timers
+        let result = match config.voting.as_ref().filter(|_| !is_halted) {
+            Some(voting) => self.tick_voting(config, voting),
+            None => Ok(()),
+        };
+
         let mut state = self.state.borrow_mut();
         state.chaos_mode.tick()?;
 
-        Ok(())
+        result
     }
 
-    pub fn set_mode(&mut self, mode: Mode, mode_type: ModeType, seconds: u32) -> Result<()> {
+    /// Applies the winner of a closed poll and opens the next one when it's due
+    fn tick_voting(&mut self, config: &Config, voting: &VotingConfig) -> Result<()> {
         let mut state = self.state.borrow_mut();
-        state.chaos_mode.set_mode(mode, mode_type, seconds)?;
+
+        if let Some(poll) = state.chaos_mode.voting.tick() {
+            if let Some(winner) = poll.winner() {
+                let mode_config = config.modes.get(winner.mode_type.key());
+                state.chaos_mode.set_mode(
+                    winner.mode,
+                    winner.mode_type,
+                    winner.seconds,
+                    &mode_config.cloned().unwrap_or_default(),
+                )?;
+            }
+        }
+
+        if state.chaos_mode.voting.is_due(voting.interval) {
+            let registry = REGISTRY.get().unwrap();
+            let options = voting::random_options(config, registry, voting.options)?;
+            state
+                .chaos_mode
+                .voting
+                .open(Poll::new(options, voting.duration));
+        }
 
         Ok(())
     }
 
+    pub fn set_mode(
+        &mut self,
+        mode: Mode,
+        mode_type: ModeType,
+        seconds: u32,
+    ) -> Result<Activation> {
+        let config = CONFIG.get().unwrap();
+        let mode_config = config.modes.get(mode_type.key()).cloned();
+
+        let mut state = self.state.borrow_mut();
+        state
+            .chaos_mode
+            .set_mode(mode, mode_type, seconds, &mode_config.unwrap_or_default())
+    }
+
     fn start_timer(&mut self) -> Result<()> {
         let plugin = self.clone();
 
@@ -179,7 +536,9 @@ impl Plugin {
             let mut plugin = plugin.clone();
 
             schedule(move |()| {
-                plugin.update().unwrap();
+                if let Err(error) = plugin.update() {
+                    Plugin::err(error.to_string().as_str());
+                }
             });
         };
 
@@ -222,6 +581,17 @@ impl Plugin {
 
         Ok(())
     }
+
+    fn notify(title: &str, message: &str, level: LogLevel) -> Result<()> {
+        let mut option_opts = Dictionary::new();
+
+        option_opts.insert("title", title);
+        option_opts.insert("timeout", 20000);
+
+        api::notify(message, level, &option_opts)?;
+
+        Ok(())
+    }
 }
 
 fn wrap_text(input: &str, max_len: usize) -> String {
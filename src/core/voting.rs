@@ -0,0 +1,157 @@
+use std::collections::HashMap;
+
+use rand::seq::SliceRandom;
+
+use crate::commands::{
+    args::Args,
+    installed_color_schemes,
+    registry::{Action, Registry},
+    Background, ColorSchemeCommand, ModeCommand, ModeSpec, ModeType,
+};
+use crate::error::Result;
+
+use super::config::{Config, Role};
+
+#[derive(Clone)]
+pub struct Poll {
+    pub options: Vec<ModeSpec>,
+    /// Option index by voter, a new vote replaces the previous one
+    pub votes: HashMap<String, usize>,
+    pub seconds: u32,
+}
+
+impl Poll {
+    pub fn new(options: Vec<ModeSpec>, seconds: u32) -> Self {
+        Self {
+            options,
+            votes: HashMap::new(),
+            seconds,
+        }
+    }
+
+    /// Registers the vote for the 1-based option, returns `false` if there's no such option
+    pub fn vote(&mut self, user: &str, option: usize) -> bool {
+        if option == 0 || option > self.options.len() {
+            return false;
+        }
+
+        self.votes.insert(user.to_owned(), option - 1);
+
+        true
+    }
+
+    pub fn tally(&self) -> Vec<usize> {
+        let mut tally = vec![0; self.options.len()];
+
+        for option in self.votes.values() {
+            tally[*option] += 1;
+        }
+
+        tally
+    }
+
+    /// Option with the most votes, ties are broken randomly
+    pub fn winner(&self) -> Option<ModeSpec> {
+        let tally = self.tally();
+        let max = tally.iter().copied().max().filter(|x| *x > 0)?;
+
+        let leaders: Vec<&ModeSpec> = self
+            .options
+            .iter()
+            .zip(tally)
+            .filter(|(_, votes)| *votes == max)
+            .map(|(option, _)| option)
+            .collect();
+
+        leaders.choose(&mut rand::thread_rng()).copied().cloned()
+    }
+
+    pub fn lines(&self) -> Vec<String> {
+        self.options
+            .iter()
+            .zip(self.tally())
+            .enumerate()
+            .map(|(index, (option, votes))| {
+                format!("  {}. {}  {}  ", index + 1, option.mode.name(), votes)
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct Voting {
+    pub poll: Option<Poll>,
+    /// Seconds since the last poll closed
+    pub idle: u32,
+}
+
+impl Voting {
+    /// Advances the poll timer, returns the poll once it's closed
+    pub fn tick(&mut self) -> Option<Poll> {
+        match &mut self.poll {
+            Some(poll) => {
+                poll.seconds = poll.seconds.saturating_sub(1);
+
+                if poll.seconds == 0 {
+                    return self.poll.take();
+                }
+            }
+            None => self.idle += 1,
+        }
+
+        None
+    }
+
+    pub fn is_due(&self, interval: u32) -> bool {
+        self.poll.is_none() && self.idle >= interval
+    }
+
+    pub fn open(&mut self, poll: Poll) {
+        self.idle = 0;
+        self.poll = Some(poll);
+    }
+}
+
+/// Random set of modes to vote for, among the commands open to everyone in every channel
+pub fn random_options(config: &Config, registry: &Registry, count: usize) -> Result<Vec<ModeSpec>> {
+    let mut rng = rand::thread_rng();
+    let mut options = Vec::new();
+
+    let is_offered = |key: &str| {
+        config.channels.iter().all(|x| x.allows(key))
+            && config
+                .permissions
+                .get(key)
+                .is_none_or(|x| x.is_allowed("", Role::default()))
+    };
+
+    for command in registry
+        .iter()
+        .filter(|x| x.is_voted() && is_offered(x.key))
+    {
+        if let Action::Mode(build) = command.action {
+            options.push(build(&Args::default(), config)?);
+        }
+    }
+
+    if is_offered("colorscheme") {
+        let schemes = installed_color_schemes()?;
+
+        for colorscheme in schemes.choose_multiple(&mut rng, count) {
+            options.push(ModeSpec {
+                mode: ColorSchemeCommand {
+                    colorscheme: colorscheme.clone(),
+                    background: Background::Default,
+                }
+                .into(),
+                mode_type: ModeType::ColorSchemeType,
+                seconds: config.commands.colorscheme.duration,
+            });
+        }
+    }
+
+    options.shuffle(&mut rng);
+    options.truncate(count);
+
+    Ok(options)
+}
@@ -5,6 +5,9 @@ pub type Result<T> = std::result::Result<T, Error>;
 
 #[derive(Debug, ThisError)]
 pub enum Error {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
     #[error(transparent)]
     Nvim(#[from] nvim_oxi::Error),
 
@@ -14,6 +17,9 @@ pub enum Error {
     #[error(transparent)]
     Api(#[from] nvim_oxi::api::Error),
 
+    #[error(transparent)]
+    Lua(#[from] nvim_oxi::mlua::Error),
+
     #[error(transparent)]
     Stream(#[from] StreamError),
 
@@ -22,4 +28,20 @@ pub enum Error {
 
     #[error(transparent)]
     Play(#[from] PlayError),
+
+    #[error(transparent)]
+    Tls(#[from] tokio_native_tls::native_tls::Error),
+
+    #[error(transparent)]
+    Join(#[from] twitch_irc::validate::Error),
+
+    #[error(transparent)]
+    Config(#[from] crate::core::config::Error),
+
+    #[error("chat: {0}")]
+    Chat(String),
+
+    /// Command refused with a reason meant for the viewer
+    #[error("{0}")]
+    Rejected(String),
 }
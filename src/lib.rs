@@ -1,8 +1,8 @@
 use nvim_oxi::{Dictionary, Result};
 
+mod chat;
 mod commands;
 mod core;
-mod twitch;
 mod error;
 
 use crate::core::plugin::Plugin;